@@ -0,0 +1,156 @@
+use super::{Felt, FieldElement, SemaphoreAir, HASH_CYCLE_LEN, NUM_HASH_ROUNDS};
+use core::fmt;
+use winterfell::{Air, EvaluationFrame, Trace, TraceTable};
+
+// CONSTRAINT FAILURE
+// ================================================================================================
+
+/// Type of a constraint which can be violated by an execution trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConstraintKind {
+    /// A boundary constraint created from one of the assertions of Semaphore AIR.
+    Assertion,
+    /// A transition constraint of Semaphore AIR.
+    Transition,
+}
+
+/// Describes a constraint which is not satisfied by an execution trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintFailure {
+    /// Type of the violated constraint.
+    pub kind: ConstraintKind,
+    /// Index of the constraint; for assertions, this is the index of the assertion in the list
+    /// returned by `SemaphoreAir::get_assertions()`.
+    pub index: usize,
+    /// Readable name of the constraint followed by its index - e.g. "key cmp [27]".
+    pub name: String,
+    /// Step at which the constraint is violated; for transition constraints this is the step
+    /// of the first row in the evaluation frame.
+    pub step: usize,
+    /// Value the constraint expects; this is always ZERO for transition constraints.
+    pub expected: Felt,
+    /// Value the constraint evaluated to.
+    pub actual: Felt,
+}
+
+impl fmt::Display for ConstraintFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} failed at step {}: expected {}, but was {}",
+            self.name, self.step, self.expected, self.actual
+        )
+    }
+}
+
+// TRACE DEBUGGER
+// ================================================================================================
+
+/// Checks the provided execution trace against all assertions and transition constraints of
+/// Semaphore AIR, and returns a list of all constraints which the trace does not satisfy.
+///
+/// The failures are sorted by step. Unlike trace validation performed by the prover, this does
+/// not stop at the first failure, and the failing constraints are labeled with readable names.
+/// An empty list means that the trace is valid.
+pub fn debug_trace(air: &SemaphoreAir, trace: &TraceTable<Felt>) -> Vec<ConstraintFailure> {
+    let mut result = Vec::new();
+
+    // check the trace against all assertions
+    for (index, assertion) in air.get_assertions().iter().enumerate() {
+        let register = assertion.register();
+        assertion.apply(trace.length(), |step, expected| {
+            let actual = trace.get(register, step);
+            if actual != expected {
+                result.push(ConstraintFailure {
+                    kind: ConstraintKind::Assertion,
+                    index,
+                    name: format!("{} [{}]", assertion_name(register, step), index),
+                    step,
+                    expected,
+                    actual,
+                });
+            }
+        });
+    }
+
+    // evaluate transition constraints for all consecutive pairs of rows; all periodic columns
+    // in Semaphore AIR are either as long as the hash cycle or as long as the trace, so values
+    // of periodic columns at a given step can be read directly from the columns
+    let periodic_columns = air.get_periodic_column_values();
+    let mut periodic_values = vec![Felt::ZERO; periodic_columns.len()];
+    let mut frame = EvaluationFrame::new(trace.width());
+    let mut evaluations = vec![Felt::ZERO; air.num_transition_constraints()];
+
+    for step in 0..trace.length() - 1 {
+        for (value, column) in periodic_values.iter_mut().zip(periodic_columns.iter()) {
+            *value = column[step % column.len()];
+        }
+        trace.read_row_into(step, frame.current_mut());
+        trace.read_row_into(step + 1, frame.next_mut());
+
+        evaluations.iter_mut().for_each(|v| *v = Felt::ZERO);
+        air.evaluate_transition(&frame, &periodic_values, &mut evaluations);
+
+        for (index, &actual) in evaluations.iter().enumerate() {
+            if actual != Felt::ZERO {
+                result.push(ConstraintFailure {
+                    kind: ConstraintKind::Transition,
+                    index,
+                    name: format!("{} [{}]", transition_name(index, step), index),
+                    step,
+                    expected: Felt::ZERO,
+                    actual,
+                });
+            }
+        }
+    }
+
+    result.sort_by_key(|failure| (failure.step, failure.kind, failure.index));
+    result
+}
+
+// CONSTRAINT NAMES
+// ================================================================================================
+
+/// Returns a name of an assertion against the specified register at the specified step.
+fn assertion_name(register: usize, step: usize) -> &'static str {
+    match register {
+        0..=3 => "merkle capacity",
+        4..=7 => "tree root",
+        8..=11 => "commitment padding",
+        12..=15 => "nullifier capacity",
+        16..=19 => "nullifier",
+        20..=23 if step == 0 => "topic",
+        20..=23 => "message",
+        24 => "index bit",
+        25 => "index accumulator",
+        26 | 27 => "message index range",
+        28..=35 => "key share",
+        _ => "unknown assertion",
+    }
+}
+
+/// Returns a name of the transition constraint at the specified index when evaluated at the
+/// specified step.
+///
+/// Some constraints are aggregated under mutually exclusive flags, and thus, the same index may
+/// refer to different constraints depending on where in a hash cycle the step is.
+fn transition_name(index: usize, step: usize) -> &'static str {
+    let is_hash_round = step % HASH_CYCLE_LEN < NUM_HASH_ROUNDS;
+    match index {
+        0..=11 if is_hash_round => "merkle hash round",
+        0..=3 => "merkle capacity reset",
+        4..=7 => "merkle left child",
+        8..=11 => "merkle right child",
+        12..=23 => "nullifier hash round",
+        24 => "index accumulator",
+        25 => "index bit",
+        26..=29 => "key cmp",
+        30 => "message index",
+        31 => "message limit",
+        32 | 33 => "message index range",
+        34..=41 => "key share",
+        42..=49 => "key share init",
+        _ => "unknown constraint",
+    }
+}
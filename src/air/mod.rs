@@ -1,4 +1,7 @@
-use super::{Digest, Felt, FieldElement, Rescue, HASH_CYCLE_LEN, TRACE_WIDTH};
+use super::{
+    Digest, Felt, FieldElement, KeyShare, Rescue, HASH_CYCLE_LEN, MESSAGE_INDEX_BITS,
+    NUM_HASH_ROUNDS, TRACE_WIDTH,
+};
 use winterfell::{
     Air, AirContext, Assertion, ByteWriter, EvaluationFrame, ProofOptions, Serializable, TraceInfo,
     TransitionConstraintDegree,
@@ -8,7 +11,9 @@ mod utils;
 use utils::{are_equal, is_binary, is_zero, not, EvaluationResult};
 
 mod rescue;
-pub use rescue::merge_evil;
+
+mod debug;
+pub use debug::{debug_trace, ConstraintFailure, ConstraintKind};
 
 // SEMAPHORE AIR
 // ================================================================================================
@@ -17,27 +22,50 @@ pub use rescue::merge_evil;
 #[derive(Debug)]
 pub struct PublicInputs {
     pub tree_root: Digest,
+    pub tree_depth: usize,
     pub nullifier: Digest,
     pub topic: Digest,
+    pub message: Digest,
+    pub message_limit: u32,
+    /// Key share revealed by the signal; this is None if the topic does not use slashing.
+    pub key_share: Option<KeyShare>,
 }
 
 impl Serializable for PublicInputs {
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
         target.write(self.tree_root);
+        // the depth of a signal is checked against the depth of the verifier, which is at most
+        // 32, before public inputs are built; thus, the depth always fits into a single byte
+        target.write_u8(self.tree_depth as u8);
         target.write(self.nullifier);
         target.write(self.topic);
+        target.write(self.message);
+        target.write_u32(self.message_limit);
+        match self.key_share {
+            Some(key_share) => {
+                target.write_u8(1);
+                target.write_u8_slice(Felt::elements_as_bytes(&key_share));
+            }
+            None => target.write_u8(0),
+        }
     }
 }
 
 /// Defines Semaphore AIR.
 ///
-/// To evaluate AIR constraints, we need to know the root of the access set Merkle tree, the
-/// nullifier of the signal, and the topic on which the signal was made.
+/// To evaluate AIR constraints, we need to know the root and the depth of the access set Merkle
+/// tree, the nullifier of the signal, the topic on which the signal was made, the hash of the
+/// message contained in the signal, and the maximum number of signals per user on the topic. If
+/// the topic uses slashing, we also need to know the key share revealed by the signal.
 pub struct SemaphoreAir {
     context: AirContext<Felt>,
     tree_root: [Felt; 4],
+    root_step: usize,
     nullifier: [Felt; 4],
     topic: [Felt; 4],
+    message: [Felt; 4],
+    message_limit: u32,
+    key_share: Option<KeyShare>,
 }
 
 impl Air for SemaphoreAir {
@@ -47,7 +75,7 @@ impl Air for SemaphoreAir {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
-        let degrees = vec![
+        let mut degrees = vec![
             // Merkle path hashing
             TransitionConstraintDegree::with_cycles(7, vec![HASH_CYCLE_LEN]),
             TransitionConstraintDegree::with_cycles(7, vec![HASH_CYCLE_LEN]),
@@ -74,20 +102,40 @@ impl Air for SemaphoreAir {
             TransitionConstraintDegree::with_cycles(7, vec![HASH_CYCLE_LEN]),
             TransitionConstraintDegree::with_cycles(7, vec![HASH_CYCLE_LEN]),
             TransitionConstraintDegree::with_cycles(7, vec![HASH_CYCLE_LEN]),
-            // index bits are binary
-            TransitionConstraintDegree::new(2),
-            // private key equality check
+            // index accumulator and index bits
             TransitionConstraintDegree::with_cycles(1, vec![HASH_CYCLE_LEN]),
+            TransitionConstraintDegree::with_cycles(2, vec![HASH_CYCLE_LEN]),
+            // nullifier secret equality check
             TransitionConstraintDegree::with_cycles(1, vec![HASH_CYCLE_LEN]),
             TransitionConstraintDegree::with_cycles(1, vec![HASH_CYCLE_LEN]),
             TransitionConstraintDegree::with_cycles(1, vec![HASH_CYCLE_LEN]),
+            TransitionConstraintDegree::with_cycles(1, vec![HASH_CYCLE_LEN]),
+            // message index range checks
+            TransitionConstraintDegree::with_cycles(1, vec![trace_info.length()]),
+            TransitionConstraintDegree::with_cycles(1, vec![trace_info.length()]),
+            TransitionConstraintDegree::new(2),
+            TransitionConstraintDegree::new(2),
         ];
 
+        // key share computation
+        degrees.extend(
+            (0..KEY_SHARE_WIDTH)
+                .map(|_| TransitionConstraintDegree::with_cycles(2, vec![HASH_CYCLE_LEN])),
+        );
+        degrees.extend(
+            (0..KEY_SHARE_WIDTH)
+                .map(|_| TransitionConstraintDegree::with_cycles(1, vec![trace_info.length()])),
+        );
+
         Self {
             context: AirContext::new(trace_info, degrees, options),
             tree_root: pub_inputs.tree_root.into(),
+            root_step: get_root_step(pub_inputs.tree_depth),
             nullifier: pub_inputs.nullifier.into(),
             topic: pub_inputs.topic.into(),
+            message: pub_inputs.message.into(),
+            message_limit: pub_inputs.message_limit,
+            key_share: pub_inputs.key_share,
         }
     }
 
@@ -99,9 +147,18 @@ impl Air for SemaphoreAir {
     /// The columns consist of:
     /// - Hash cycle column, which has a repeating pattern of 7 ones followed by a single zero.
     /// - Key mask column, which has a repeating pattern of a single one followed by 7 zeros.
+    /// - First row mask column, which has a single one followed by zeros; its length is the same
+    ///   as the length of the trace.
     /// - 24 round constant columns for Rescue Prime hash function.
     fn get_periodic_column_values(&self) -> Vec<Vec<Felt>> {
-        let mut result = vec![HASH_CYCLE_MASK.to_vec(), KEY_CMP_MASK.to_vec()];
+        let mut first_row_mask = vec![Felt::ZERO; self.trace_length()];
+        first_row_mask[0] = Felt::ONE;
+
+        let mut result = vec![
+            HASH_CYCLE_MASK.to_vec(),
+            KEY_CMP_MASK.to_vec(),
+            first_row_mask,
+        ];
         result.append(&mut rescue::get_round_constants());
         result
     }
@@ -113,18 +170,34 @@ impl Air for SemaphoreAir {
     /// prover and verifier into boundary constraints.
     ///
     /// These assertions enforce that:
-    /// - The trace terminates with Merkle tree root in columns [4, 5, 6, 7].
+    /// - Merkle tree root is in columns [4, 5, 6, 7] at the last step of the hash cycle for the
+    ///   last level of the tree; the trace may be padded with additional hash cycles after this
+    ///   step, and these cycles are not used.
     /// - Columns [16, 16, 18, 19] at step 7 contain value of the nullifier.
     /// - Topic was inserted into columns [20, 21, 22, 23] at the first step.
-    /// - Columns [0, 1, 2, 3] are reset to zeros every 8 steps to (8, 0, 0, 0).
-    /// - Columns [12, 13, 14, 15] are set to (8, 0, 0, 0) at the first step.
+    /// - Columns [0, 1, 2, 3] are set to (8, 0, 0, 0) at the first step; transition constraints
+    ///   reset them to the same values at the start of every following hash cycle.
+    /// - Columns [12, 14, 15] are set to (8, 0, 0) at the first step; column 13 holds the message
+    ///   index, which is constrained by transition constraints instead.
+    /// - The index bit column starts with ZERO, and the index accumulator column is ONE (the
+    ///   sentinel bit which marks the root of the Merkle tree) at the same step as the root.
+    /// - At the first step of the second hash cycle, the index bit is ZERO and columns
+    ///   [8, 9, 10, 11] are set to ZEROs. Thus, the hash of the identity secrets computed during
+    ///   the first hash cycle is hashed with zero into the identity commitment, which is the leaf
+    ///   of the Merkle tree.
+    /// - Message hash was inserted into columns [20, 21, 22, 23] at the first step of the second
+    ///   hash cycle. The nullifier section of the trace is not used after the nullifier is
+    ///   computed, and thus, these cells are free to hold the message hash.
+    /// - Both message index range check columns reach ONE (the sentinel bit) at step 15.
+    /// - If the topic uses slashing, columns [28..36] contain the key share at the first step of
+    ///   the second hash cycle.
     fn get_assertions(&self) -> Vec<Assertion<Felt>> {
-        let last_step = self.trace_length() - 1;
-        vec![
-            Assertion::single(4, last_step, self.tree_root[0]),
-            Assertion::single(5, last_step, self.tree_root[1]),
-            Assertion::single(6, last_step, self.tree_root[2]),
-            Assertion::single(7, last_step, self.tree_root[3]),
+        let root_step = self.root_step;
+        let mut result = vec![
+            Assertion::single(4, root_step, self.tree_root[0]),
+            Assertion::single(5, root_step, self.tree_root[1]),
+            Assertion::single(6, root_step, self.tree_root[2]),
+            Assertion::single(7, root_step, self.tree_root[3]),
             Assertion::single(16, 7, self.nullifier[0]),
             Assertion::single(17, 7, self.nullifier[1]),
             Assertion::single(18, 7, self.nullifier[2]),
@@ -133,7 +206,39 @@ impl Air for SemaphoreAir {
             Assertion::single(21, 0, self.topic[1]),
             Assertion::single(22, 0, self.topic[2]),
             Assertion::single(23, 0, self.topic[3]),
-        ]
+            Assertion::single(12, 0, HASH_CAPACITY[0]),
+            Assertion::single(14, 0, HASH_CAPACITY[2]),
+            Assertion::single(15, 0, HASH_CAPACITY[3]),
+            Assertion::single(0, 0, HASH_CAPACITY[0]),
+            Assertion::single(1, 0, HASH_CAPACITY[1]),
+            Assertion::single(2, 0, HASH_CAPACITY[2]),
+            Assertion::single(3, 0, HASH_CAPACITY[3]),
+            Assertion::single(24, 0, Felt::ZERO),
+            Assertion::single(25, root_step, Felt::ONE),
+            Assertion::single(24, HASH_CYCLE_LEN, Felt::ZERO),
+            Assertion::single(8, HASH_CYCLE_LEN, Felt::ZERO),
+            Assertion::single(9, HASH_CYCLE_LEN, Felt::ZERO),
+            Assertion::single(10, HASH_CYCLE_LEN, Felt::ZERO),
+            Assertion::single(11, HASH_CYCLE_LEN, Felt::ZERO),
+            Assertion::single(20, HASH_CYCLE_LEN, self.message[0]),
+            Assertion::single(21, HASH_CYCLE_LEN, self.message[1]),
+            Assertion::single(22, HASH_CYCLE_LEN, self.message[2]),
+            Assertion::single(23, HASH_CYCLE_LEN, self.message[3]),
+            Assertion::single(26, MESSAGE_INDEX_BITS, Felt::ONE),
+            Assertion::single(27, MESSAGE_INDEX_BITS, Felt::ONE),
+        ];
+
+        if let Some(key_share) = self.key_share {
+            for (i, &value) in key_share.iter().enumerate() {
+                result.push(Assertion::single(
+                    KEY_SHARE_OFFSET + i,
+                    HASH_CYCLE_LEN,
+                    value,
+                ));
+            }
+        }
+
+        result
     }
 
     // TRANSITION CONSTRAINTS
@@ -153,14 +258,15 @@ impl Air for SemaphoreAir {
     ) {
         let current = frame.current();
         let next = frame.next();
-        // expected state width is 25 field elements
+        // expected state width is 36 field elements
         debug_assert_eq!(TRACE_WIDTH, current.len());
         debug_assert_eq!(TRACE_WIDTH, next.len());
 
         // split periodic values into masks and Rescue round constants
         let hash_flag = periodic_values[0];
         let key_cmp_flag = periodic_values[1];
-        let ark = &periodic_values[2..];
+        let first_row_flag = periodic_values[2];
+        let ark = &periodic_values[3..];
 
         // when hash_flag = 1, constraints for Rescue round are enforced for Merkle path
         // hashing and for nullifier hashing
@@ -181,16 +287,20 @@ impl Air for SemaphoreAir {
 
         // for Merkle path hashing, when hash_flag = 0, make sure accumulated hash is placed in the
         // right place in the hash state for the next round of hashing. Specifically: when index
-        // bit = 0 accumulated hash must go into columns [4, 5, 6, 7], and when index bit = 0, it must
+        // bit = 0 accumulated hash must go into columns [4, 5, 6, 7], and when index bit = 1, it must
         // go into columns [8, 9, 10, 11]; also, make sure the capacity columns are reset to ZERO.
         let hash_init_flag = not(hash_flag);
         let bit = next[24];
         let not_bit = not(bit);
 
-        result.agg_constraint(1, hash_init_flag, are_equal(E::from(8u8), next[0]));
-        result.agg_constraint(2, hash_init_flag, is_zero(next[1]));
-        result.agg_constraint(3, hash_init_flag, is_zero(next[2]));
-        result.agg_constraint(4, hash_init_flag, is_zero(next[3]));
+        result.agg_constraint(
+            0,
+            hash_init_flag,
+            are_equal(E::from(HASH_CAPACITY[0]), next[0]),
+        );
+        result.agg_constraint(1, hash_init_flag, is_zero(next[1]));
+        result.agg_constraint(2, hash_init_flag, is_zero(next[2]));
+        result.agg_constraint(3, hash_init_flag, is_zero(next[3]));
 
         result.agg_constraint(4, hash_init_flag, not_bit * are_equal(current[4], next[4]));
         result.agg_constraint(5, hash_init_flag, not_bit * are_equal(current[5], next[5]));
@@ -205,16 +315,94 @@ impl Air for SemaphoreAir {
         // no additional constraints are imposed for nullifier hashing because we don't care what
         // happens to nullifier columns after step 7
 
-        // enforce that values in the bit column must be binary
-        result[24] = is_binary(current[24]);
+        // index bits may change only at cycle boundaries, when a new Merkle path node is inserted
+        // into the hash state. Column 25 accumulates the bits from the root of the tree down to
+        // the leaf: it is ONE (the sentinel) at the root step, and at every boundary it must be
+        // the case that acc = 2 * acc' + bit'. Thus, during the first hash cycle the accumulator
+        // holds 2^(depth + 1) + 2 * key_index, where the lowest bit is the bit of the identity
+        // commitment cycle (which is always ZERO). In padding cycles after the root step, the
+        // accumulator drops to ZERO. Both constraints are expressed via the accumulator, which is
+        // never constant, and this keeps their degrees the same for all key indexes; for the same
+        // reason the new bit is checked to be binary as acc - 2 * acc'.
+        result.agg_constraint(24, hash_flag, are_equal(current[25], next[25]));
+        result.agg_constraint(
+            24,
+            hash_init_flag,
+            are_equal(current[25], E::from(2u32) * next[25] + bit),
+        );
+
+        result.agg_constraint(25, hash_flag, are_equal(current[24], next[24]));
+        result.agg_constraint(
+            25,
+            hash_init_flag,
+            is_binary(current[25] - E::from(2u32) * next[25]),
+        );
 
         // finally, we need to make sure that at steps which are multiples of 8 (e.g. 0, 16, 32 etc.)
         // values in columns [4, 5, 6, 7] are the same as in columns [16, 17, 18, 19]; technically,
-        // we care about this only for step 0, but it is easier to enforce it for all multiples of 8
-        result.agg_constraint(25, key_cmp_flag, are_equal(current[4], current[16]));
-        result.agg_constraint(26, key_cmp_flag, are_equal(current[5], current[17]));
-        result.agg_constraint(27, key_cmp_flag, are_equal(current[6], current[18]));
-        result.agg_constraint(28, key_cmp_flag, are_equal(current[7], current[19]));
+        // we care about this only for step 0 (where both hold the nullifier secret), but it is
+        // easier to enforce it for all multiples of 8
+        result.agg_constraint(26, key_cmp_flag, are_equal(current[4], current[16]));
+        result.agg_constraint(27, key_cmp_flag, are_equal(current[5], current[17]));
+        result.agg_constraint(28, key_cmp_flag, are_equal(current[6], current[18]));
+        result.agg_constraint(29, key_cmp_flag, are_equal(current[7], current[19]));
+
+        // the message index is placed into column 13 (the second capacity element of the
+        // nullifier hash state) at step 0, and it must be in the range [0, message_limit). This is
+        // enforced by decomposing both the index and (message_limit - 1 - index) into 15 bits.
+        // Columns 26 and 27 hold the two values with a sentinel bit 2^15 added, and are shifted
+        // right by one bit at every step; thus, they reach ONE (the sentinel) at step 15 only if
+        // both values are smaller than 2^15. Same as for the index accumulator, the sentinel keeps
+        // the columns from being constant, and thus, keeps the constraint degrees stable.
+        let sentinel = E::from(MESSAGE_INDEX_SENTINEL);
+        result.agg_constraint(
+            30,
+            first_row_flag,
+            are_equal(current[13] + sentinel, current[26]),
+        );
+        result.agg_constraint(
+            31,
+            first_row_flag,
+            are_equal(
+                current[26] + current[27],
+                E::from(self.message_limit) - E::ONE + sentinel + sentinel,
+            ),
+        );
+        result[32] = is_binary(current[26] - E::from(2u32) * next[26]);
+        result[33] = is_binary(current[27] - E::from(2u32) * next[27]);
+
+        // columns [28..36] hold a key share, which is a point on 8 degree-1 polynomials with the
+        // identity secret as their constant terms: share = secret + slope * x. The slope for the
+        // nullifier secret (columns [28, 29, 30, 31]) is the part of the nullifier hash output
+        // which is not revealed (columns [20, 21, 22, 23] at step 7), and the slope for the
+        // trapdoor (columns [32, 33, 34, 35]) is the capacity portion of this output (columns
+        // [12, 13, 14, 15] at step 7). All 8 polynomials share a single x coordinate which binds
+        // all elements of the message hash (see get_key_share_x()); thus, a signal reveals only
+        // one point on every polynomial. The columns are set to the identity secret at step 0,
+        // remain unchanged within every hash cycle, and at every cycle boundary, the product of
+        // the slope columns and the x coordinate of the next values in columns [20..24] is added
+        // to them. After the first cycle, this yields the key share; the remaining cycles do not
+        // affect the share at step 8, and are not used.
+        let x = get_key_share_x(&next[20..24]);
+        for i in 0..KEY_SHARE_WIDTH {
+            let slope = if i < 4 {
+                current[20 + i]
+            } else {
+                current[8 + i]
+            };
+            let column = KEY_SHARE_OFFSET + i;
+            result.agg_constraint(34 + i, hash_flag, are_equal(current[column], next[column]));
+            result.agg_constraint(
+                34 + i,
+                hash_init_flag,
+                are_equal(current[column] + slope * x, next[column]),
+            );
+            result.agg_constraint(
+                34 + KEY_SHARE_WIDTH + i,
+                first_row_flag,
+                are_equal(current[4 + i], current[column]),
+            );
+        }
     }
 
     // BOILERPLATE
@@ -225,6 +413,144 @@ impl Air for SemaphoreAir {
     }
 }
 
+// TRACE LENGTH
+// ================================================================================================
+
+/// Returns the step at which the root of an access set Merkle tree of the specified depth is
+/// computed.
+///
+/// The computation requires one hash cycle for hashing the identity secrets, one hash cycle for
+/// hashing the result into the identity commitment, and one hash cycle for every level of the
+/// tree.
+pub fn get_root_step(depth: usize) -> usize {
+    (depth + 2) * HASH_CYCLE_LEN - 1
+}
+
+/// Returns the length of the execution trace for an access set Merkle tree of the specified
+/// depth; this is the number of steps required to compute the root padded to the next power of
+/// two.
+pub fn get_trace_length(depth: usize) -> usize {
+    (get_root_step(depth) + 1).next_power_of_two()
+}
+
+// NULLIFIER
+// ================================================================================================
+
+/// Capacity portion of the hash state at the start of every hash computed in the trace.
+///
+/// The first element is set to 8 (the number of elements to be hashed), and the rest are set to
+/// ZERO. This is the same as the initial capacity used by Rp64_256::merge().
+pub const HASH_CAPACITY: [Felt; 4] = [Felt::new(8), Felt::ZERO, Felt::ZERO, Felt::ZERO];
+
+/// Sentinel bit which is added to the values decomposed in the message index range check
+/// columns; this is equal to 2^15.
+pub const MESSAGE_INDEX_SENTINEL: Felt = Felt::new(1 << MESSAGE_INDEX_BITS);
+
+/// Returns the hash state from which a nullifier is computed.
+///
+/// The state consists of the capacity elements, followed by the nullifier secret, followed by the
+/// hash of the topic. The message index is placed into the second capacity element (which is
+/// otherwise ZERO), and thus, every message index yields a different nullifier. This state is
+/// placed into columns [12..24] at the first step of the trace.
+pub fn init_nullifier_state(
+    nullifier_secret: [Felt; 4],
+    topic: [Felt; 4],
+    message_index: u32,
+) -> [Felt; 12] {
+    let mut state = [Felt::ZERO; 12];
+    state[..4].copy_from_slice(&HASH_CAPACITY);
+    state[1] = Felt::from(message_index);
+    state[4..8].copy_from_slice(&nullifier_secret);
+    state[8..].copy_from_slice(&topic);
+    state
+}
+
+/// Computes a nullifier for the specified nullifier secret, topic, and message index.
+///
+/// For message index 0, the nullifier is simply hash(nullifier_secret, topic). This is the only
+/// place where nullifiers are computed natively; the computation follows the nullifier section of
+/// the execution trace step by step, and thus the result is always the same as the value which
+/// Semaphore AIR expects in columns [16, 17, 18, 19] at step 7.
+pub fn compute_nullifier(
+    nullifier_secret: [Felt; 4],
+    topic: [Felt; 4],
+    message_index: u32,
+) -> Digest {
+    let state = hash_nullifier_state(nullifier_secret, topic, message_index);
+    let nullifier: [Felt; 4] = [state[4], state[5], state[6], state[7]];
+    nullifier.into()
+}
+
+/// Computes a key share for the specified identity secret, topic, and message index at the
+/// point defined by the message hash.
+///
+/// The identity secret consists of the nullifier secret followed by the trapdoor. The share is
+/// computed as secret + slope * x, where x is derived from the message hash by
+/// [get_key_share_x()], and the slope is the part of the output of the nullifier hash which is not
+/// revealed: for the nullifier secret, this is the second half of the rate portion (the first half
+/// is the nullifier itself), and for the trapdoor, this is the capacity portion. Thus, the slope
+/// is the same for all signals with the same nullifier, but cannot be computed without the
+/// nullifier secret. The result is the same as the value in columns [28..36] at step 8.
+pub fn compute_key_share(
+    identity_secret: [Felt; 8],
+    topic: [Felt; 4],
+    message_index: u32,
+    message: [Felt; 4],
+) -> KeyShare {
+    let mut nullifier_secret = [Felt::ZERO; 4];
+    nullifier_secret.copy_from_slice(&identity_secret[..4]);
+    let state = hash_nullifier_state(nullifier_secret, topic, message_index);
+
+    let x = get_key_share_x(&message);
+    let mut result = identity_secret;
+    for (i, value) in result.iter_mut().enumerate() {
+        let slope = if i < 4 { state[8 + i] } else { state[i - 4] };
+        *value += slope * x;
+    }
+    result
+}
+
+/// Returns the x coordinate of the key share revealed by a signal with the provided message hash.
+///
+/// The coordinate is computed as m0 + b * m1 + b^2 * m2 + b^3 * m3, where m0..m3 are the
+/// elements of the message hash and b is [KEY_SHARE_X_BASE]; thus, every element of the message
+/// hash affects the coordinate. The coordinate is a single field element, and thus, two messages
+/// with the same coordinate can be found with a birthday search over roughly 2^32 message hashes;
+/// such messages reveal the same key share, and this is an accepted limitation of slashing (see
+/// [recover_identity_from_double_signal()](crate::recover_identity_from_double_signal)).
+pub fn get_key_share_x<E: FieldElement + From<Felt>>(message: &[E]) -> E {
+    let base = E::from(KEY_SHARE_X_BASE);
+    message
+        .iter()
+        .rev()
+        .fold(E::ZERO, |result, &element| result * base + element)
+}
+
+/// Applies Rescue permutation to the initial nullifier hash state, and returns the result.
+fn hash_nullifier_state(
+    nullifier_secret: [Felt; 4],
+    topic: [Felt; 4],
+    message_index: u32,
+) -> [Felt; 12] {
+    let mut state = init_nullifier_state(nullifier_secret, topic, message_index);
+    for round in 0..NUM_HASH_ROUNDS {
+        Rescue::apply_round(&mut state, round);
+    }
+    state
+}
+
+// KEY SHARE
+// ================================================================================================
+
+/// Index of the first key share column in the execution trace.
+pub const KEY_SHARE_OFFSET: usize = 28;
+
+/// Number of key share columns in the execution trace.
+const KEY_SHARE_WIDTH: usize = 8;
+
+/// Base of the combination of message hash elements which yields the x coordinate of a key share.
+const KEY_SHARE_X_BASE: Felt = Felt::new(7);
+
 // MASKS
 // ================================================================================================
 const HASH_CYCLE_MASK: [Felt; HASH_CYCLE_LEN] = [
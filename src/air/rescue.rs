@@ -1,65 +1,4 @@
-use super::{Digest, are_equal, EvaluationResult, Felt, FieldElement, Rescue, HASH_CYCLE_LEN};
-use core::ops::Range;
-use core::convert::TryInto;
-use winterfell::{
-    math::{fields::f64::BaseElement as BaseElement, StarkField},
-};
-
-// Evil stuffs
-const STATE_WIDTH: usize = 12;
-const RATE_RANGE: Range<usize> = 4..12;
-const RATE_WIDTH: usize = RATE_RANGE.end - RATE_RANGE.start;
-const CAPACITY_RANGE: Range<usize> = 0..4;
-const DIGEST_RANGE: Range<usize> = 4..8;
-pub fn merge_evil(values: &[Digest; 2]) -> Digest {
-    // initialize the state by copying the digest elements into the rate portion of the state
-    // (8 total elements), and set the first capacity element to 8 (the number of elements to
-    // be hashed).
-    let mut state = [BaseElement::ZERO; STATE_WIDTH];
-    state[RATE_RANGE].copy_from_slice(Digest::digests_as_elements(values));
-    // state[CAPACITY_RANGE.start] = BaseElement::new(RATE_WIDTH as u64);
-    state[CAPACITY_RANGE.start] = BaseElement::new(1 as u64);
-
-    // apply the Rescue permutation and return the first four elements of the state
-    apply_permutation(&mut state);
-    Digest::new(state[DIGEST_RANGE].try_into().unwrap())
-}
-/// Applies Rescue-XLIX permutation to the provided state.
-pub fn apply_permutation(state: &mut [BaseElement; STATE_WIDTH]) {
-    // implementation is based on algorithm 3 from <https://eprint.iacr.org/2020/1143.pdf>
-    // apply round function 7 times; this provides 128-bit security with 40% security margin
-    for i in 0..NUM_ROUNDS {
-        apply_round(state, i);
-    }
-}
-/// Rescue-XLIX round function.
-#[inline(always)]
-pub fn apply_round(state: &mut [BaseElement; STATE_WIDTH], round: usize) {
-    // apply first half of Rescue round
-    apply_sbox(state);
-    apply_mds(state);
-    add_constants(state, &ARK1[round]);
-
-    // apply second half of Rescue round
-    apply_inv_sbox(state);
-    apply_mds(state);
-    add_constants(state, &ARK2[round]);
-}
-#[inline(always)]
-fn add_constants(state: &mut [BaseElement; STATE_WIDTH], ark: &[BaseElement; STATE_WIDTH]) {
-    state.iter_mut().zip(ark).for_each(|(s, &k)| *s += k);
-}
-#[inline(always)]
-fn exp_acc<B: StarkField, const N: usize, const M: usize>(base: [B; N], tail: [B; N]) -> [B; N] {
-    let mut result = base;
-    for _ in 0..M {
-        result.iter_mut().for_each(|r| *r = r.square());
-    }
-    result.iter_mut().zip(tail).for_each(|(r, t)| *r *= t);
-    result
-}
-
-////////////////////////////////
+use super::{are_equal, EvaluationResult, Felt, FieldElement, Rescue, HASH_CYCLE_LEN};
 
 // RESCUE ROUND CONSTRAINTS
 // ================================================================================================
@@ -107,42 +46,6 @@ fn apply_sbox<E: FieldElement + From<Felt>>(state: &mut [E; STATE_WIDTH]) {
     });
 }
 
-#[inline(always)]
-fn apply_inv_sbox(state: &mut [BaseElement; STATE_WIDTH]) {
-    // compute base^10540996611094048183 using 72 multiplications per array element
-    // 10540996611094048183 = b1001001001001001001001001001000110110110110110110110110110110111
-
-    // compute base^10
-    let mut t1 = *state;
-    t1.iter_mut().for_each(|t| *t = t.square());
-
-    // compute base^100
-    let mut t2 = t1;
-    t2.iter_mut().for_each(|t| *t = t.square());
-
-    // compute base^100100
-    let t3 = exp_acc::<BaseElement, STATE_WIDTH, 3>(t2, t2);
-
-    // compute base^100100100100
-    let t4 = exp_acc::<BaseElement, STATE_WIDTH, 6>(t3, t3);
-
-    // compute base^100100100100100100100100
-    let t5 = exp_acc::<BaseElement, STATE_WIDTH, 12>(t4, t4);
-
-    // compute base^100100100100100100100100100100
-    let t6 = exp_acc::<BaseElement, STATE_WIDTH, 6>(t5, t3);
-
-    // compute base^1001001001001001001001001001000100100100100100100100100100100
-    let t7 = exp_acc::<BaseElement, STATE_WIDTH, 31>(t6, t6);
-
-    // compute base^1001001001001001001001001001000110110110110110110110110110110111
-    for (i, s) in state.iter_mut().enumerate() {
-        let a = (t7[i].square() * t6[i]).square().square();
-        let b = t1[i] * t2[i] * *s;
-        *s = a * b;
-    }
-}
-
 #[inline(always)]
 fn apply_mds<E: FieldElement + From<Felt>>(state: &mut [E; STATE_WIDTH]) {
     let mut result = [E::ZERO; STATE_WIDTH];
@@ -189,7 +92,7 @@ pub fn get_round_constants() -> Vec<Vec<Felt>> {
 // RESCUE CONSTANTS
 // ================================================================================================
 
-// const STATE_WIDTH: usize = Rescue::STATE_WIDTH;
+const STATE_WIDTH: usize = Rescue::STATE_WIDTH;
 const NUM_ROUNDS: usize = Rescue::NUM_ROUNDS;
 
 /// Rescue MDS matrix
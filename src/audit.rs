@@ -0,0 +1,95 @@
+use super::{get_removed_leaf, Digest};
+use std::fmt;
+use winterfell::crypto::Digest as HasherDigest;
+
+// ACCESS SET CHANGE
+// ================================================================================================
+
+/// Defines a change of a single public key in an access set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessSetChange {
+    /// The public key was revoked, and its slot was replaced with the removed leaf.
+    Removed { pub_key: Digest },
+    /// The public key was replaced with a new public key (e.g., on key rotation).
+    Replaced {
+        old_pub_key: Digest,
+        new_pub_key: Digest,
+    },
+}
+
+// AUDIT RECORD
+// ================================================================================================
+
+/// Describes a mutation of an access set.
+///
+/// Every removal or replacement of a public key in an access set produces a new root of the set,
+/// and an audit record which binds the change to the roots before and after it. A sequence of
+/// audit records can be reviewed to check which keys were revoked or rotated, and to link every
+/// root of the set to the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// Index of the slot which was changed.
+    pub key_idx: usize,
+    /// The change made to the slot.
+    pub change: AccessSetChange,
+    /// Root of the access set before the change.
+    pub old_root: Digest,
+    /// Root of the access set after the change.
+    pub new_root: Digest,
+}
+
+impl AuditRecord {
+    /// Returns a record of the slot at the specified index changing from the old leaf to the new
+    /// leaf; the change is a removal if the new leaf is the removed leaf.
+    pub(crate) fn new(
+        key_idx: usize,
+        old_leaf: Digest,
+        new_leaf: Digest,
+        old_root: Digest,
+        new_root: Digest,
+    ) -> Self {
+        let change = if new_leaf == get_removed_leaf() {
+            AccessSetChange::Removed { pub_key: old_leaf }
+        } else {
+            AccessSetChange::Replaced {
+                old_pub_key: old_leaf,
+                new_pub_key: new_leaf,
+            }
+        };
+        Self {
+            key_idx,
+            change,
+            old_root,
+            new_root,
+        }
+    }
+}
+
+impl fmt::Display for AuditRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.change {
+            AccessSetChange::Removed { pub_key } => write!(
+                f,
+                "removed key {} at index {}",
+                hex::encode(pub_key.as_bytes()),
+                self.key_idx
+            )?,
+            AccessSetChange::Replaced {
+                old_pub_key,
+                new_pub_key,
+            } => write!(
+                f,
+                "replaced key {} at index {} with key {}",
+                hex::encode(old_pub_key.as_bytes()),
+                self.key_idx,
+                hex::encode(new_pub_key.as_bytes())
+            )?,
+        }
+        write!(
+            f,
+            "; root {} -> {}",
+            hex::encode(self.old_root.as_bytes()),
+            hex::encode(self.new_root.as_bytes())
+        )
+    }
+}
@@ -0,0 +1,265 @@
+use super::{
+    get_empty_leaf, AccessSet, Digest, IncrementalAccessSet, SemaphoreError,
+    ACCESS_SET_FORMAT_VERSION, ACCESS_SET_MAGIC, MAX_TREE_DEPTH,
+};
+use std::{fs, path::Path};
+use winter_utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
+// ACCESS SET DESCRIPTOR
+// ================================================================================================
+
+/// Describes an access set by its root, its depth, and its leaves.
+///
+/// The leaves are the public keys in the access set in the order of their indexes; removed keys
+/// are represented by the removed leaf, and empty leaves padding the tree are not included.
+///
+/// A descriptor is plain data: reading a descriptor does not check that the leaves hash into the
+/// root. [AccessSet::from_descriptor()] and [IncrementalAccessSet::from_descriptor()] rebuild the
+/// tree from the leaves and perform this check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessSetDescriptor {
+    pub root: Digest,
+    pub depth: usize,
+    pub leaves: Vec<Digest>,
+}
+
+impl AccessSetDescriptor {
+    /// Returns a byte representation of this descriptor.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.write_into(&mut result);
+        result
+    }
+
+    /// Returns a descriptor read from the provided bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes do not represent a valid descriptor, or if there are bytes
+    /// left after the descriptor.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SemaphoreError> {
+        let mut source = SliceReader::new(bytes);
+        let descriptor = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes.into());
+        }
+        Ok(descriptor)
+    }
+}
+
+impl Serializable for AccessSetDescriptor {
+    /// Serializes this descriptor as follows:
+    /// - Magic header [ACCESS_SET_MAGIC] (4 bytes), and format version
+    ///   [ACCESS_SET_FORMAT_VERSION] (1 byte).
+    /// - Depth of the access set (1 byte).
+    /// - Number of leaves (4 bytes), followed by the leaves (32 bytes each).
+    /// - Root of the access set (32 bytes).
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8_slice(&ACCESS_SET_MAGIC);
+        target.write_u8(ACCESS_SET_FORMAT_VERSION);
+        target.write_u8(self.depth as u8);
+        target.write_u32(self.leaves.len() as u32);
+        for &leaf in self.leaves.iter() {
+            target.write(leaf);
+        }
+        target.write(self.root);
+    }
+}
+
+impl Deserializable for AccessSetDescriptor {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        if source.read_u8_array::<4>()? != ACCESS_SET_MAGIC {
+            return Err(DeserializationError::InvalidValue(
+                "invalid access set magic header".to_string(),
+            ));
+        }
+        let version = source.read_u8()?;
+        if version != ACCESS_SET_FORMAT_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported access set format version {}; expected version {}",
+                version, ACCESS_SET_FORMAT_VERSION
+            )));
+        }
+
+        // leaves are read one by one so that a malformed number of leaves cannot cause a large
+        // allocation
+        let depth = source.read_u8()? as usize;
+        let num_leaves = source.read_u32()? as usize;
+        let mut leaves = Vec::new();
+        for _ in 0..num_leaves {
+            leaves.push(Digest::read_from(source)?);
+        }
+        let root = Digest::read_from(source)?;
+
+        Ok(Self {
+            root,
+            depth,
+            leaves,
+        })
+    }
+}
+
+// ACCESS SET FORMAT
+// ================================================================================================
+
+/// Defines the encoding of an access set file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessSetFormat {
+    /// Compact binary encoding (see [AccessSetDescriptor::to_bytes()]).
+    Binary,
+    /// JSON encoding (see [AccessSetDescriptor::to_json()]).
+    Text,
+}
+
+// ACCESS SET
+// ================================================================================================
+
+impl AccessSet {
+    /// Returns a descriptor of this access set.
+    pub fn descriptor(&self) -> AccessSetDescriptor {
+        AccessSetDescriptor {
+            root: self.root(),
+            depth: self.depth(),
+            leaves: self.key_tree.leaves()[..self.num_keys].to_vec(),
+        }
+    }
+
+    /// Returns an access set rebuilt from the leaves of the provided descriptor.
+    ///
+    /// Removed keys keep their slots, and thus, indexes of all keys are the same as in the
+    /// described access set. The access set uses the default verification policy.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The depth of the descriptor is not between 1 and 32.
+    /// - The descriptor has no leaves, or any of its leaves is the empty leaf.
+    /// - The depth of the rebuilt tree does not match the depth of the descriptor.
+    /// - The root of the rebuilt tree does not match the root of the descriptor.
+    pub fn from_descriptor(descriptor: &AccessSetDescriptor) -> Result<Self, SemaphoreError> {
+        if !(1..=MAX_TREE_DEPTH).contains(&descriptor.depth) {
+            return Err(SemaphoreError::InvalidDepth(descriptor.depth));
+        }
+        if descriptor.leaves.is_empty() {
+            return Err(SemaphoreError::InvalidSetSize(0));
+        }
+        if descriptor.leaves.contains(&get_empty_leaf()) {
+            return Err(SemaphoreError::InvalidPubKey);
+        }
+
+        let access_set = Self::from_leaves(descriptor.leaves.clone())?;
+        if access_set.depth() != descriptor.depth {
+            return Err(SemaphoreError::DepthMismatch {
+                expected: descriptor.depth,
+                actual: access_set.depth(),
+            });
+        }
+        if access_set.root() != descriptor.root {
+            return Err(SemaphoreError::RootMismatch {
+                expected: descriptor.root,
+                actual: access_set.root(),
+            });
+        }
+        Ok(access_set)
+    }
+
+    /// Writes the leaves, the depth, and the root of this access set into the file at the
+    /// specified path using the specified format; the file is created if it does not exist, and
+    /// is overwritten otherwise.
+    ///
+    /// The verification policy of the access set is not saved.
+    ///
+    /// # Errors
+    /// Returns [SemaphoreError::Io] if the file could not be written.
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: AccessSetFormat,
+    ) -> Result<(), SemaphoreError> {
+        let descriptor = self.descriptor();
+        match format {
+            AccessSetFormat::Binary => fs::write(path, descriptor.to_bytes())?,
+            AccessSetFormat::Text => fs::write(path, descriptor.to_json())?,
+        }
+        Ok(())
+    }
+
+    /// Returns an access set loaded from the file at the specified path, which must have been
+    /// written in the specified format.
+    ///
+    /// The tree is rebuilt from the leaves in the file, and its root is checked against the root
+    /// recorded in the file; thus, a corrupted or tampered file is rejected. The access set uses
+    /// the default verification policy.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The file could not be read ([SemaphoreError::Io]).
+    /// - The file is not a valid encoding of an access set ([SemaphoreError::Deserialization]).
+    /// - The access set could not be rebuilt from the file (e.g., [SemaphoreError::RootMismatch]
+    ///   for a tampered file); see [AccessSet::from_descriptor()] for details.
+    pub fn load<P: AsRef<Path>>(path: P, format: AccessSetFormat) -> Result<Self, SemaphoreError> {
+        let bytes = fs::read(path)?;
+        let descriptor = match format {
+            AccessSetFormat::Binary => AccessSetDescriptor::from_bytes(&bytes)?,
+            AccessSetFormat::Text => {
+                let json = String::from_utf8(bytes).map_err(|_| {
+                    DeserializationError::InvalidValue("file is not valid UTF-8".to_string())
+                })?;
+                AccessSetDescriptor::from_json(&json)?
+            }
+        };
+
+        Self::from_descriptor(&descriptor)
+    }
+}
+
+// INCREMENTAL ACCESS SET
+// ================================================================================================
+
+impl IncrementalAccessSet {
+    /// Returns a descriptor of the current state of this access set.
+    ///
+    /// The descriptor records the depth of this access set, which may be greater than the
+    /// smallest depth which fits its keys; thus, it should be loaded with
+    /// [IncrementalAccessSet::from_descriptor()].
+    pub fn descriptor(&self) -> AccessSetDescriptor {
+        AccessSetDescriptor {
+            root: self.root(),
+            depth: self.depth(),
+            leaves: self.leaves().to_vec(),
+        }
+    }
+
+    /// Returns an access set rebuilt from the leaves of the provided descriptor, which keeps up
+    /// to `history_size` most recent roots.
+    ///
+    /// Unlike [AccessSet::from_descriptor()], the tree is rebuilt at the depth of the descriptor
+    /// rather than at the smallest depth which fits the leaves; thus, a partially filled access
+    /// set is restored with its capacity, and more keys can be appended to it. Removed keys keep
+    /// their slots. The root history holds only the root of the descriptor, and the access set
+    /// uses the default verification policy.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The depth of the descriptor is not between 1 and 32, or the history size is 0.
+    /// - The descriptor has more leaves than a tree of its depth can hold, or any of its leaves
+    ///   is the empty leaf.
+    /// - The root of the rebuilt tree does not match the root of the descriptor.
+    pub fn from_descriptor(
+        descriptor: &AccessSetDescriptor,
+        history_size: usize,
+    ) -> Result<Self, SemaphoreError> {
+        if descriptor.leaves.contains(&get_empty_leaf()) {
+            return Err(SemaphoreError::InvalidPubKey);
+        }
+
+        let access_set = Self::from_leaves(descriptor.depth, &descriptor.leaves, history_size)?;
+        if access_set.root() != descriptor.root {
+            return Err(SemaphoreError::RootMismatch {
+                expected: descriptor.root,
+                actual: access_set.root(),
+            });
+        }
+        Ok(access_set)
+    }
+}
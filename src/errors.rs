@@ -0,0 +1,280 @@
+use super::{Digest, GroupId, HasherDigest, KeyParseError, MAX_MESSAGE_LIMIT, MAX_TREE_DEPTH};
+use std::{fmt, io};
+use winter_utils::DeserializationError;
+use winterfell::{ProverError, VerifierError};
+
+// SEMAPHORE ERROR
+// ================================================================================================
+
+/// Defines errors which can occur when access sets are built and updated, when signals are made,
+/// verified, and accepted, and when any of the objects of this crate is decoded.
+///
+/// Errors are compared by value, except for [SemaphoreError::Io] and [SemaphoreError::Store]
+/// errors, which are compared by their [io::ErrorKind] only.
+#[derive(Debug)]
+pub enum SemaphoreError {
+    /// An access set cannot hold the specified number of public keys (e.g., an access set must
+    /// contain at least one key, and an incremental access set cannot grow beyond its capacity).
+    InvalidSetSize(usize),
+    /// An access set cannot have a Merkle tree of the specified depth.
+    InvalidDepth(usize),
+    /// A root history cannot hold the specified number of roots.
+    InvalidHistorySize(usize),
+    /// A public key is one of the leaves reserved for slots which do not hold a public key.
+    InvalidPubKey,
+    /// A public or a private key could not be parsed.
+    InvalidKey(KeyParseError),
+    /// An identity could not be parsed.
+    InvalidIdentity(String),
+    /// The public key of an identity is not in the access set.
+    KeyNotInSet,
+    /// The index of a key is out of bounds.
+    InvalidIndex(usize),
+    /// The public key at the specified index has been removed from the access set.
+    KeyRemoved(usize),
+    /// A message index is not smaller than the message limit of the topic.
+    InvalidMessageIndex { index: u32, limit: u32 },
+    /// A topic cannot have the specified message limit.
+    InvalidMessageLimit(u32),
+    /// The epoch of an external nullifier cannot be advanced because it is already u64::MAX.
+    EpochOverflow,
+    /// Epochs of external nullifiers cannot be zero seconds long.
+    InvalidEpochLength,
+    /// A poll cannot be created with the specified options or topic.
+    InvalidPoll(String),
+    /// A poll has no option at the specified index.
+    InvalidOption(usize),
+    /// A transcript does not describe a valid poll.
+    InvalidTranscript(Box<SemaphoreError>),
+    /// Proof options are outside of the range supported by the prover.
+    InvalidProofOptions(String),
+    /// A trace cannot be built from an empty Merkle path.
+    EmptyMerklePath,
+    /// The STARK proof of a signal could not be generated.
+    ProofGeneration(ProverError),
+    /// The trace length of a proof does not match the depth of the access set; this means that
+    /// the proof was made for an access set of a different depth.
+    InvalidTraceLength { expected: usize, actual: usize },
+    /// A signal was made for an access set of a different depth than the depth expected by a
+    /// verifier.
+    SignalDepthMismatch { expected: usize, actual: usize },
+    /// A proof does not satisfy the verification policy.
+    Policy(String),
+    /// A signal does not contain a key share required by a topic which uses slashing.
+    MissingKeyShare,
+    /// A signal contains a key share, but the topic does not use slashing.
+    UnexpectedKeyShare,
+    /// The STARK proof of a signal is not valid.
+    VerificationFailed(VerifierError),
+    /// No group with the specified ID is registered.
+    UnknownGroup(GroupId),
+    /// Signals cannot be used as evidence of double signalling.
+    InvalidEvidence(String),
+    /// The depth recorded for an access set does not match the depth of the tree built from its
+    /// leaves, or the depth recorded in a poll transcript does not match the expected depth.
+    DepthMismatch { expected: usize, actual: usize },
+    /// The root recorded for an access set does not match the root of the tree built from its
+    /// leaves; this means that the leaves or the root have been corrupted or tampered with. This
+    /// is also the case when the root recorded in a poll transcript does not match the expected
+    /// root.
+    RootMismatch { expected: Digest, actual: Digest },
+    /// A signal was made against a root which is not among the roots accepted by a verifier.
+    UnknownRoot(Digest),
+    /// A signal with the same nullifier has already been accepted on the same topic.
+    DuplicateNullifier { topic: Digest, nullifier: Digest },
+    /// A nullifier store could not be opened, read, or updated.
+    Store(io::Error),
+    /// An access set file could not be read or written.
+    Io(io::Error),
+    /// Bytes or a JSON string are not a valid encoding of a signal, evidence, a transcript, an
+    /// access set descriptor, a key, or an identity; this includes access set files.
+    Deserialization(DeserializationError),
+}
+
+impl fmt::Display for SemaphoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSetSize(num_keys) => {
+                write!(f, "access set cannot hold {} public keys", num_keys)
+            }
+            Self::InvalidDepth(depth) => write!(
+                f,
+                "access set depth must be between 1 and {}, but was {}",
+                MAX_TREE_DEPTH, depth
+            ),
+            Self::InvalidHistorySize(size) => write!(
+                f,
+                "root history size must be greater than 0, but was {}",
+                size
+            ),
+            Self::InvalidPubKey => write!(
+                f,
+                "public key must not be the empty leaf or the removed leaf"
+            ),
+            Self::InvalidKey(err) => write!(f, "invalid key encoding: {}", err),
+            Self::InvalidIdentity(err) => write!(f, "invalid identity encoding: {}", err),
+            Self::KeyNotInSet => {
+                write!(f, "public key for the provided identity could not be found")
+            }
+            Self::InvalidIndex(index) => write!(f, "index {} is out of bounds", index),
+            Self::KeyRemoved(index) => write!(f, "key at index {} has been removed", index),
+            Self::InvalidMessageIndex { index, limit } => write!(
+                f,
+                "message index {} is not smaller than the message limit of {}",
+                index, limit
+            ),
+            Self::InvalidMessageLimit(limit) => write!(
+                f,
+                "message limit must be between 1 and {}, but was {}",
+                MAX_MESSAGE_LIMIT, limit
+            ),
+            Self::EpochOverflow => write!(f, "epoch cannot be greater than {}", u64::MAX),
+            Self::InvalidEpochLength => write!(f, "epoch length must be greater than zero"),
+            Self::InvalidPoll(err) => write!(f, "invalid poll: {}", err),
+            Self::InvalidOption(option) => write!(f, "option {} does not exist", option),
+            Self::InvalidTranscript(err) => write!(f, "invalid transcript: {}", err),
+            Self::InvalidProofOptions(err) => write!(f, "invalid proof options: {}", err),
+            Self::EmptyMerklePath => write!(f, "Merkle path must not be empty"),
+            Self::ProofGeneration(err) => write!(f, "failed to generate proof: {}", err),
+            Self::InvalidTraceLength { expected, actual } => write!(
+                f,
+                "invalid proof trace length: expected {}, but was {}",
+                expected, actual
+            ),
+            Self::SignalDepthMismatch { expected, actual } => write!(
+                f,
+                "signal was made for an access set of depth {}, but expected depth {}",
+                actual, expected
+            ),
+            Self::Policy(err) => write!(f, "proof violates verification policy: {}", err),
+            Self::MissingKeyShare => write!(
+                f,
+                "signal does not contain a key share required by the topic"
+            ),
+            Self::UnexpectedKeyShare => write!(
+                f,
+                "signal contains a key share, but the topic does not use slashing"
+            ),
+            Self::VerificationFailed(err) => write!(f, "proof verification failed: {}", err),
+            Self::UnknownGroup(group_id) => write!(f, "group {} is not registered", group_id),
+            Self::InvalidEvidence(err) => write!(f, "invalid slashing evidence: {}", err),
+            Self::DepthMismatch { expected, actual } => write!(
+                f,
+                "access set depth mismatch: expected {}, but was {}",
+                expected, actual
+            ),
+            Self::RootMismatch { expected, actual } => write!(
+                f,
+                "access set root mismatch: expected {}, but was {}",
+                hex::encode(expected.as_bytes()),
+                hex::encode(actual.as_bytes())
+            ),
+            Self::UnknownRoot(root) => write!(
+                f,
+                "signal was made against unknown access set root {}",
+                hex::encode(root.as_bytes())
+            ),
+            Self::DuplicateNullifier { nullifier, .. } => write!(
+                f,
+                "nullifier {} has already been used on this topic",
+                hex::encode(nullifier.as_bytes())
+            ),
+            Self::Store(err) => write!(f, "nullifier store error: {}", err),
+            Self::Io(err) => write!(f, "access set file error: {}", err),
+            Self::Deserialization(err) => write!(f, "invalid encoding: {}", err),
+        }
+    }
+}
+
+impl PartialEq for SemaphoreError {
+    fn eq(&self, other: &Self) -> bool {
+        use SemaphoreError::*;
+        match (self, other) {
+            (InvalidSetSize(a), InvalidSetSize(b)) => a == b,
+            (InvalidDepth(a), InvalidDepth(b)) => a == b,
+            (InvalidHistorySize(a), InvalidHistorySize(b)) => a == b,
+            (InvalidPubKey, InvalidPubKey) => true,
+            (InvalidKey(a), InvalidKey(b)) => a == b,
+            (InvalidIdentity(a), InvalidIdentity(b)) => a == b,
+            (KeyNotInSet, KeyNotInSet) => true,
+            (InvalidIndex(a), InvalidIndex(b)) => a == b,
+            (KeyRemoved(a), KeyRemoved(b)) => a == b,
+            (
+                InvalidMessageIndex { index, limit },
+                InvalidMessageIndex {
+                    index: other_index,
+                    limit: other_limit,
+                },
+            ) => index == other_index && limit == other_limit,
+            (InvalidMessageLimit(a), InvalidMessageLimit(b)) => a == b,
+            (EpochOverflow, EpochOverflow) => true,
+            (InvalidEpochLength, InvalidEpochLength) => true,
+            (InvalidPoll(a), InvalidPoll(b)) => a == b,
+            (InvalidOption(a), InvalidOption(b)) => a == b,
+            (InvalidTranscript(a), InvalidTranscript(b)) => a == b,
+            (InvalidProofOptions(a), InvalidProofOptions(b)) => a == b,
+            (EmptyMerklePath, EmptyMerklePath) => true,
+            (ProofGeneration(a), ProofGeneration(b)) => a == b,
+            (
+                InvalidTraceLength { expected, actual },
+                InvalidTraceLength {
+                    expected: other_expected,
+                    actual: other_actual,
+                },
+            ) => expected == other_expected && actual == other_actual,
+            (
+                SignalDepthMismatch { expected, actual },
+                SignalDepthMismatch {
+                    expected: other_expected,
+                    actual: other_actual,
+                },
+            ) => expected == other_expected && actual == other_actual,
+            (Policy(a), Policy(b)) => a == b,
+            (MissingKeyShare, MissingKeyShare) => true,
+            (UnexpectedKeyShare, UnexpectedKeyShare) => true,
+            (VerificationFailed(a), VerificationFailed(b)) => a == b,
+            (UnknownGroup(a), UnknownGroup(b)) => a == b,
+            (InvalidEvidence(a), InvalidEvidence(b)) => a == b,
+            (
+                DepthMismatch { expected, actual },
+                DepthMismatch {
+                    expected: other_expected,
+                    actual: other_actual,
+                },
+            ) => expected == other_expected && actual == other_actual,
+            (
+                RootMismatch { expected, actual },
+                RootMismatch {
+                    expected: other_expected,
+                    actual: other_actual,
+                },
+            ) => expected == other_expected && actual == other_actual,
+            (UnknownRoot(a), UnknownRoot(b)) => a == b,
+            (
+                DuplicateNullifier { topic, nullifier },
+                DuplicateNullifier {
+                    topic: other_topic,
+                    nullifier: other_nullifier,
+                },
+            ) => topic == other_topic && nullifier == other_nullifier,
+            (Store(a), Store(b)) => a.kind() == b.kind(),
+            (Io(a), Io(b)) => a.kind() == b.kind(),
+            (Deserialization(a), Deserialization(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl std::error::Error for SemaphoreError {}
+
+impl From<io::Error> for SemaphoreError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<DeserializationError> for SemaphoreError {
+    fn from(err: DeserializationError) -> Self {
+        Self::Deserialization(err)
+    }
+}
@@ -0,0 +1,458 @@
+use super::{
+    build_signal, get_empty_leaf, get_removed_leaf, get_trace_length, validate_pub_key,
+    AuditRecord, Digest, Hasher, Identity, NullifierStore, PubKey, Rescue, SemaphoreError,
+    SemaphoreProver, Signal, SignalVerifier, Topic, VerificationPolicy, MAX_TREE_DEPTH,
+};
+use std::collections::VecDeque;
+
+// INCREMENTAL ACCESS SET
+// ================================================================================================
+
+/// Defines an access set to which public keys can be appended over time.
+///
+/// Unlike an [AccessSet](super::AccessSet), an incremental access set is backed by a Merkle tree
+/// of a fixed depth, in which leaves not yet holding a public key are empty leaves. Appending a
+/// public key updates only the nodes on the path from the new leaf to the root, and thus, takes
+/// O(depth) hashes. Nodes of empty subtrees are not stored, and thus, the memory needed by the
+/// access set is proportional to the number of keys in it rather than to its capacity.
+///
+/// Public keys can also be removed from the access set or replaced with other keys; this takes
+/// O(depth) hashes as well, and produces an [AuditRecord] of the change.
+///
+/// Every change produces a new root. The access set keeps a bounded history of the most recent
+/// roots, and accepts signals made against any root still in the history; this way, signals made
+/// by users who have not yet learned about the latest appends remain valid for a while. Signals
+/// made against roots which have been evicted from the history are rejected. Removing or
+/// replacing a key revokes all previous roots, since the removed key is a member of the access
+/// set under each of them.
+pub struct IncrementalAccessSet {
+    depth: usize,
+    nodes: Vec<Vec<Digest>>,
+    empty_nodes: Vec<Digest>,
+    roots: RootHistory,
+    policy: VerificationPolicy,
+}
+
+impl IncrementalAccessSet {
+    /// Returns a new empty access set of the specified depth which keeps up to `history_size`
+    /// most recent roots.
+    ///
+    /// The access set can hold up to 2^depth public keys.
+    ///
+    /// # Errors
+    /// Returns an error if the depth is not between 1 and 32, or if the history size is 0.
+    pub fn new(depth: usize, history_size: usize) -> Result<Self, SemaphoreError> {
+        if !(1..=MAX_TREE_DEPTH).contains(&depth) {
+            return Err(SemaphoreError::InvalidDepth(depth));
+        }
+
+        // compute the roots of empty subtrees for every level of the tree; the root of an empty
+        // subtree at level 0 is the empty leaf itself
+        let mut empty_nodes = Vec::with_capacity(depth + 1);
+        empty_nodes.push(get_empty_leaf());
+        for i in 0..depth {
+            empty_nodes.push(Rescue::merge(&[empty_nodes[i], empty_nodes[i]]));
+        }
+
+        let roots = RootHistory::new(empty_nodes[depth], history_size)?;
+
+        Ok(Self {
+            depth,
+            nodes: vec![Vec::new(); depth],
+            empty_nodes,
+            roots,
+            policy: VerificationPolicy::default(),
+        })
+    }
+
+    /// Returns an access set of the specified depth which holds the provided leaves and keeps up
+    /// to `history_size` most recent roots; the leaves may include removed leaves.
+    ///
+    /// The root history holds only the root of the resulting tree.
+    ///
+    /// # Errors
+    /// Returns an error if the depth is not between 1 and 32, if the history size is 0, or if
+    /// there are more leaves than the access set can hold.
+    pub(crate) fn from_leaves(
+        depth: usize,
+        leaves: &[Digest],
+        history_size: usize,
+    ) -> Result<Self, SemaphoreError> {
+        let mut access_set = Self::new(depth, history_size)?;
+        if leaves.len() > access_set.capacity() {
+            return Err(SemaphoreError::InvalidSetSize(leaves.len()));
+        }
+
+        for (key_idx, &leaf) in leaves.iter().enumerate() {
+            access_set.nodes[0].push(leaf);
+            access_set.update_path(key_idx);
+        }
+        access_set.roots = RootHistory::new(access_set.root(), history_size)?;
+        Ok(access_set)
+    }
+
+    /// Returns this access set with its verification policy replaced by the provided one.
+    pub fn with_policy(mut self, policy: VerificationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the policy which signals must satisfy to be accepted by this access set.
+    pub fn policy(&self) -> &VerificationPolicy {
+        &self.policy
+    }
+
+    /// Returns the current root of this access set.
+    pub fn root(&self) -> Digest {
+        self.roots.current()
+    }
+
+    /// Returns the recent roots of this access set ordered from the oldest to the current one.
+    pub fn root_history(&self) -> impl Iterator<Item = &Digest> {
+        self.roots.iter()
+    }
+
+    /// Returns true if the provided root is still in the root history of this access set.
+    pub fn is_known_root(&self, root: &Digest) -> bool {
+        self.roots.contains(root)
+    }
+
+    /// Returns the leaves of this access set which hold public keys or removed leaves.
+    pub(crate) fn leaves(&self) -> &[Digest] {
+        &self.nodes[0]
+    }
+
+    /// Returns the root history of this access set.
+    pub(crate) fn roots(&self) -> &RootHistory {
+        &self.roots
+    }
+
+    /// Returns the number of recent roots this access set keeps in its root history.
+    pub fn history_size(&self) -> usize {
+        self.roots.size()
+    }
+
+    /// Returns the depth of the Merkle tree which holds the keys of this access set.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the maximum number of public keys this access set can hold.
+    pub fn capacity(&self) -> usize {
+        1 << self.depth
+    }
+
+    /// Returns the number of public keys appended to this access set; this includes slots of
+    /// removed keys.
+    pub fn num_keys(&self) -> usize {
+        self.nodes[0].len()
+    }
+
+    /// Returns the length of the execution trace of signals made for this access set.
+    pub fn trace_length(&self) -> usize {
+        get_trace_length(self.depth)
+    }
+
+    /// Appends the provided public key to this access set and returns the index of the key.
+    ///
+    /// The root of the access set is updated, and the previous root is retained in the root
+    /// history; if the history is full, the oldest root is evicted from it.
+    ///
+    /// # Errors
+    /// Returns an error if the access set is full, or if the key is the empty leaf or the
+    /// removed leaf.
+    pub fn append(&mut self, pub_key: PubKey) -> Result<usize, SemaphoreError> {
+        let key_idx = self.num_keys();
+        if key_idx == self.capacity() {
+            return Err(SemaphoreError::InvalidSetSize(key_idx + 1));
+        }
+        validate_pub_key(&pub_key)?;
+
+        self.nodes[0].push(pub_key.0);
+        self.update_path(key_idx);
+        Ok(key_idx)
+    }
+
+    /// Removes the public key at the specified index from this access set, and returns an audit
+    /// record of the removal.
+    ///
+    /// The slot of the key is replaced with the removed leaf, for which no signal can be made.
+    /// Indexes of other keys do not change, and the slot is not reused by later appends. All
+    /// previous roots are removed from the root history, and thus, the removed key cannot signal
+    /// against any of them; other users must obtain new key paths to signal again.
+    ///
+    /// # Errors
+    /// Returns an error if the key index is not smaller than the number of public keys in this
+    /// access set, or if the key at this index has already been removed.
+    pub fn remove(&mut self, key_idx: usize) -> Result<AuditRecord, SemaphoreError> {
+        self.update_key(key_idx, get_removed_leaf())
+    }
+
+    /// Replaces the public key at the specified index in this access set with the provided key
+    /// (e.g., to rotate the key of a user), and returns an audit record of the replacement.
+    ///
+    /// As with [IncrementalAccessSet::remove()], all previous roots are removed from the root
+    /// history, and thus, the old key cannot signal against any of them.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The key index is not smaller than the number of public keys in this access set.
+    /// - The key at this index has been removed.
+    /// - The new key is the empty leaf or the removed leaf.
+    pub fn replace(
+        &mut self,
+        key_idx: usize,
+        new_pub_key: PubKey,
+    ) -> Result<AuditRecord, SemaphoreError> {
+        validate_pub_key(&new_pub_key)?;
+        self.update_key(key_idx, new_pub_key.0)
+    }
+
+    /// Returns a Merkle path to the key at the specified index against the current root.
+    ///
+    /// The first element in the path will be the public key itself.
+    ///
+    /// # Errors
+    /// Returns an error if the key index is not smaller than the number of public keys in this
+    /// access set.
+    pub fn get_key_path(&self, key_idx: usize) -> Result<Vec<Digest>, SemaphoreError> {
+        if key_idx >= self.num_keys() {
+            return Err(SemaphoreError::InvalidIndex(key_idx));
+        }
+
+        let mut path = Vec::with_capacity(self.depth + 1);
+        path.push(self.node(0, key_idx));
+        let mut index = key_idx;
+        for level in 0..self.depth {
+            path.push(self.node(level, index ^ 1));
+            index >>= 1;
+        }
+        Ok(path)
+    }
+
+    /// Returns a signal of the user with specified identity on the specified topic, made against
+    /// the current root of this access set.
+    ///
+    /// This is the same as [IncrementalAccessSet::make_rate_limited_signal()] with message
+    /// index 0.
+    ///
+    /// # Errors
+    /// Returns an error if the public key for the provided identity is not in this access set,
+    /// or if the proof could not be generated.
+    pub fn make_signal(
+        &self,
+        identity: &Identity,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        prover: &SemaphoreProver,
+    ) -> Result<Signal, SemaphoreError> {
+        self.make_rate_limited_signal(identity, topic, message, 0, prover)
+    }
+
+    /// Returns a signal of the user with specified identity on the specified topic, using the
+    /// specified message index, made against the current root of this access set.
+    ///
+    /// See [AccessSet::make_rate_limited_signal()](super::AccessSet::make_rate_limited_signal)
+    /// for details.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The message index is not smaller than the message limit of the topic.
+    /// - The public key for the provided identity is not in this access set.
+    /// - The proof could not be generated.
+    pub fn make_rate_limited_signal(
+        &self,
+        identity: &Identity,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        message_index: u32,
+        prover: &SemaphoreProver,
+    ) -> Result<Signal, SemaphoreError> {
+        let pub_key = PubKey::new(identity);
+        let key_idx = self.nodes[0]
+            .iter()
+            .position(|&v| v == pub_key.0)
+            .ok_or(SemaphoreError::KeyNotInSet)?;
+        let key_path = self.get_key_path(key_idx)?;
+
+        build_signal(
+            identity,
+            key_idx,
+            &key_path,
+            topic.into(),
+            message,
+            message_index,
+            prover,
+        )
+    }
+
+    /// Returns Ok(()) if the provided signal is a valid signal with the specified message on the
+    /// specified topic made against any root in the root history of this access set.
+    ///
+    /// The root of the signal is looked up in the history, which takes time linear in the size of
+    /// the history, and the proof is then verified once against this root.
+    pub fn verify_signal(
+        &self,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        signal: Signal,
+    ) -> Result<(), SemaphoreError> {
+        self.verifier().verify_signal(topic, message, signal)
+    }
+
+    /// Verifies the provided signal and records its nullifier in the provided store.
+    ///
+    /// The nullifier of a signal does not depend on the root the signal was made against, and
+    /// thus, a user cannot signal again on the same topic after the root of the access set
+    /// changes.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The signal is not valid (as defined by [IncrementalAccessSet::verify_signal()]).
+    /// - A signal with the same nullifier has already been recorded in the store for this topic.
+    /// - The store could not be read or updated.
+    pub fn accept_signal<S: NullifierStore>(
+        &self,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        signal: Signal,
+        store: &mut S,
+    ) -> Result<(), SemaphoreError> {
+        self.verifier().accept_signal(topic, message, signal, store)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the node at the specified index at the specified level of the tree; level 0
+    /// contains the leaves of the tree.
+    fn node(&self, level: usize, index: usize) -> Digest {
+        match self.nodes[level].get(index) {
+            Some(&node) => node,
+            None => self.empty_nodes[level],
+        }
+    }
+
+    /// Sets the leaf at the specified index to the provided value, revokes all previous roots,
+    /// and returns an audit record of the change.
+    fn update_key(
+        &mut self,
+        key_idx: usize,
+        new_leaf: Digest,
+    ) -> Result<AuditRecord, SemaphoreError> {
+        if key_idx >= self.num_keys() {
+            return Err(SemaphoreError::InvalidIndex(key_idx));
+        }
+        let old_leaf = self.nodes[0][key_idx];
+        if old_leaf == get_removed_leaf() {
+            return Err(SemaphoreError::KeyRemoved(key_idx));
+        }
+
+        let old_root = self.root();
+        self.nodes[0][key_idx] = new_leaf;
+        self.update_path(key_idx);
+        self.roots.revoke_previous();
+        Ok(AuditRecord::new(
+            key_idx,
+            old_leaf,
+            new_leaf,
+            old_root,
+            self.root(),
+        ))
+    }
+
+    /// Updates all nodes on the path from the leaf at the specified index to the root, and makes
+    /// the new root the current root of this access set.
+    ///
+    /// A node is appended to its level if it was a root of an empty subtree before.
+    fn update_path(&mut self, key_idx: usize) {
+        let mut index = key_idx;
+        for level in 0..self.depth {
+            let parent =
+                Rescue::merge(&[self.node(level, index & !1), self.node(level, index | 1)]);
+            index >>= 1;
+            if level + 1 == self.depth {
+                self.roots.push(parent);
+            } else if index < self.nodes[level + 1].len() {
+                self.nodes[level + 1][index] = parent;
+            } else {
+                self.nodes[level + 1].push(parent);
+            }
+        }
+    }
+
+    /// Returns a verifier of signals against the root history, the depth, and the policy of this
+    /// access set.
+    fn verifier(&self) -> SignalVerifier {
+        SignalVerifier::from_root_history(&self.roots, self.depth, self.policy.clone())
+    }
+}
+
+// ROOT HISTORY
+// ================================================================================================
+
+/// A bounded history of the most recent roots of an access set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RootHistory {
+    roots: VecDeque<Digest>,
+    size: usize,
+}
+
+impl RootHistory {
+    /// Returns a new history holding only the provided root, which can grow up to the specified
+    /// number of roots.
+    ///
+    /// # Errors
+    /// Returns an error if the size is 0.
+    pub fn new(root: Digest, size: usize) -> Result<Self, SemaphoreError> {
+        if size == 0 {
+            return Err(SemaphoreError::InvalidHistorySize(size));
+        }
+        let mut roots = VecDeque::with_capacity(size);
+        roots.push_back(root);
+        Ok(Self { roots, size })
+    }
+
+    /// Returns the most recent root in this history.
+    pub fn current(&self) -> Digest {
+        // new() starts the history with a root, and roots are only evicted when a new root is
+        // added, or by revoke_previous() which keeps the current root
+        *self.roots.back().expect("root history is empty")
+    }
+
+    /// Returns the maximum number of roots in this history.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Makes the provided root the most recent root in this history, evicting the oldest root
+    /// if the history is full.
+    pub fn push(&mut self, root: Digest) {
+        if self.roots.len() == self.size {
+            self.roots.pop_front();
+        }
+        self.roots.push_back(root);
+    }
+
+    /// Removes all roots but the most recent one from this history.
+    pub fn revoke_previous(&mut self) {
+        let current = self.current();
+        self.roots.clear();
+        self.roots.push_back(current);
+    }
+
+    /// Returns true if the provided root is in this history.
+    pub fn contains(&self, root: &Digest) -> bool {
+        self.roots.contains(root)
+    }
+
+    /// Returns an iterator over the roots ordered from the oldest to the most recent one.
+    pub fn iter(&self) -> impl Iterator<Item = &Digest> {
+        self.roots.iter()
+    }
+
+    /// Returns the roots ordered from the oldest to the most recent one.
+    pub fn to_vec(&self) -> Vec<Digest> {
+        self.roots.iter().copied().collect()
+    }
+}
@@ -0,0 +1,325 @@
+use super::{
+    AccessSetDescriptor, Digest, Felt, Identity, KeyParseError, PrivKey, PubKey, SemaphoreError,
+    Signal, MAX_SIGNAL_PROOF_SIZE, MAX_TREE_DEPTH,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
+use winter_utils::{Deserializable, DeserializationError, SliceReader};
+use winterfell::{crypto::Digest as HasherDigest, math::FieldElement, StarkProof};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Version of the JSON schemas written by `to_json()` methods.
+pub const JSON_SCHEMA_VERSION: u64 = 1;
+
+// PUBLIC KEY
+// ================================================================================================
+
+impl PubKey {
+    /// Returns a JSON encoding of this public key.
+    ///
+    /// The encoding is an object with fields `version` (the schema version) and `key` (the key as
+    /// a hex string, as in the [Display](std::fmt::Display) implementation).
+    pub fn to_json(&self) -> String {
+        to_json(&KeyJson {
+            version: JSON_SCHEMA_VERSION,
+            key: hex::encode(self.0.as_bytes()),
+        })
+    }
+
+    /// Returns a public key read from the provided JSON encoding.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a valid JSON encoding of a public key.
+    pub fn from_json(json: &str) -> Result<Self, SemaphoreError> {
+        let object: KeyJson = from_json(json)?;
+        Ok(decode_key(&object.key, "key")?)
+    }
+}
+
+// PRIVATE KEY
+// ================================================================================================
+
+impl PrivKey {
+    /// Returns a JSON encoding of this private key.
+    ///
+    /// The encoding is an object with fields `version` (the schema version) and `key` (the key as
+    /// a hex string, as in the [Display](std::fmt::Display) implementation).
+    pub fn to_json(&self) -> String {
+        to_json(&KeyJson {
+            version: JSON_SCHEMA_VERSION,
+            key: hex::encode(Felt::elements_as_bytes(&self.elements())),
+        })
+    }
+
+    /// Returns a private key read from the provided JSON encoding.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a valid JSON encoding of a private key.
+    pub fn from_json(json: &str) -> Result<Self, SemaphoreError> {
+        let object: KeyJson = from_json(json)?;
+        Ok(decode_key(&object.key, "key")?)
+    }
+}
+
+// IDENTITY
+// ================================================================================================
+
+impl Identity {
+    /// Returns a JSON encoding of this identity.
+    ///
+    /// The encoding is an object with fields `version` (the schema version), `nullifier_secret`
+    /// and `trapdoor` (the secrets as hex strings).
+    pub fn to_json(&self) -> String {
+        to_json(&IdentityJson {
+            version: JSON_SCHEMA_VERSION,
+            nullifier_secret: hex::encode(Felt::elements_as_bytes(&self.nullifier_secret())),
+            trapdoor: hex::encode(Felt::elements_as_bytes(&self.trapdoor())),
+        })
+    }
+
+    /// Returns an identity read from the provided JSON encoding.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a valid JSON encoding of an identity.
+    pub fn from_json(json: &str) -> Result<Self, SemaphoreError> {
+        let object: IdentityJson = from_json(json)?;
+        let nullifier_secret: PrivKey = decode_key(&object.nullifier_secret, "nullifier_secret")?;
+        let trapdoor: PrivKey = decode_key(&object.trapdoor, "trapdoor")?;
+        Ok(Self::new(nullifier_secret.elements(), trapdoor.elements()))
+    }
+}
+
+// SIGNAL
+// ================================================================================================
+
+impl Signal {
+    /// Returns a JSON encoding of this signal.
+    ///
+    /// The encoding is an object with the following fields:
+    /// - `version`: the schema version.
+    /// - `root`: the root of the access set the signal was made for as a hex string.
+    /// - `depth`: the depth of the access set the signal was made for as a number.
+    /// - `nullifier`: the nullifier as a hex string.
+    /// - `key_share`: the key share as a hex string, or `null` if the signal has no key share.
+    /// - `proof`: the proof bytes as a base64 string.
+    pub fn to_json(&self) -> String {
+        to_json(&SignalJson {
+            version: JSON_SCHEMA_VERSION,
+            root: hex::encode(self.root.as_bytes()),
+            depth: self.depth,
+            nullifier: hex::encode(self.nullifier.as_bytes()),
+            key_share: self
+                .key_share
+                .map(|key_share| hex::encode(Felt::elements_as_bytes(&key_share))),
+            proof: BASE64.encode(self.proof.to_bytes()),
+        })
+    }
+
+    /// Returns a signal read from the provided JSON encoding.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a valid JSON encoding of a signal, or if the proof
+    /// is larger than [MAX_SIGNAL_PROOF_SIZE].
+    pub fn from_json(json: &str) -> Result<Self, SemaphoreError> {
+        let object: SignalJson = from_json(json)?;
+        if !(1..=MAX_TREE_DEPTH).contains(&object.depth) {
+            return Err(invalid_field(
+                "depth",
+                &format!("must be a number between 1 and {}", MAX_TREE_DEPTH),
+            )
+            .into());
+        }
+        let key_share = match object.key_share {
+            Some(key_share) => {
+                let elements = decode_elements(&key_share, 8, "key_share")?;
+                let mut result = [Felt::ZERO; 8];
+                result.copy_from_slice(&elements);
+                Some(result)
+            }
+            None => None,
+        };
+
+        if object.proof.len() / 4 * 3 > MAX_SIGNAL_PROOF_SIZE {
+            return Err(DeserializationError::InvalidValue(format!(
+                "signal proof size exceeds the maximum of {} bytes",
+                MAX_SIGNAL_PROOF_SIZE
+            ))
+            .into());
+        }
+        let proof = BASE64
+            .decode(&object.proof)
+            .map_err(|_| invalid_field("proof", "is not a valid base64 string"))?;
+        let proof = StarkProof::from_bytes(&proof)?;
+
+        Ok(Self {
+            root: decode_digest(&object.root, "root")?,
+            depth: object.depth,
+            nullifier: decode_digest(&object.nullifier, "nullifier")?,
+            proof,
+            key_share,
+        })
+    }
+}
+
+// ACCESS SET DESCRIPTOR
+// ================================================================================================
+
+impl AccessSetDescriptor {
+    /// Returns a JSON encoding of this descriptor.
+    ///
+    /// The encoding is an object with the following fields:
+    /// - `version`: the schema version.
+    /// - `root`: the root of the access set as a hex string.
+    /// - `depth`: the depth of the access set as a number.
+    /// - `leaves`: an array of the leaves of the access set as hex strings.
+    pub fn to_json(&self) -> String {
+        to_json(&DescriptorJson {
+            version: JSON_SCHEMA_VERSION,
+            root: hex::encode(self.root.as_bytes()),
+            depth: self.depth,
+            leaves: self
+                .leaves
+                .iter()
+                .map(|leaf| hex::encode(leaf.as_bytes()))
+                .collect(),
+        })
+    }
+
+    /// Returns a descriptor read from the provided JSON encoding.
+    ///
+    /// The leaves are not checked against the root; see [AccessSetDescriptor] for details.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a valid JSON encoding of a descriptor.
+    pub fn from_json(json: &str) -> Result<Self, SemaphoreError> {
+        let object: DescriptorJson = from_json(json)?;
+        Ok(Self {
+            root: decode_digest(&object.root, "root")?,
+            depth: object.depth,
+            leaves: object
+                .leaves
+                .iter()
+                .map(|leaf| decode_digest(leaf, "leaves"))
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+// JSON SCHEMAS
+// ================================================================================================
+
+/// Schema version which starts every JSON object; other fields are ignored when only the
+/// version is read.
+#[derive(Deserialize)]
+struct SchemaVersion {
+    version: u64,
+}
+
+/// JSON schema of public and private keys.
+#[derive(Serialize, Deserialize)]
+struct KeyJson {
+    version: u64,
+    key: String,
+}
+
+/// JSON schema of identities.
+#[derive(Serialize, Deserialize)]
+struct IdentityJson {
+    version: u64,
+    nullifier_secret: String,
+    trapdoor: String,
+}
+
+/// JSON schema of signals.
+#[derive(Serialize, Deserialize)]
+struct SignalJson {
+    version: u64,
+    root: String,
+    depth: usize,
+    nullifier: String,
+    key_share: Option<String>,
+    proof: String,
+}
+
+/// JSON schema of access set descriptors.
+#[derive(Serialize, Deserialize)]
+struct DescriptorJson {
+    version: u64,
+    root: String,
+    depth: usize,
+    leaves: Vec<String>,
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn to_json<T: Serialize>(object: &T) -> String {
+    // JSON objects of this module hold only strings, integers, and lists of these, which can
+    // always be encoded
+    serde_json::to_string(object).expect("failed to encode JSON")
+}
+
+/// Reads an object of the specified schema from the provided string; the schema version of the
+/// object is checked before the rest of the object is read.
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, DeserializationError> {
+    let SchemaVersion { version } = serde_json::from_str(json).map_err(map_json_error)?;
+    if version != JSON_SCHEMA_VERSION {
+        return Err(DeserializationError::InvalidValue(format!(
+            "unsupported JSON schema version {}; expected version {}",
+            version, JSON_SCHEMA_VERSION
+        )));
+    }
+    serde_json::from_str(json).map_err(map_json_error)
+}
+
+/// Decodes a hex string holding the specified number of field elements.
+fn decode_elements(
+    value: &str,
+    num_elements: usize,
+    field: &str,
+) -> Result<Vec<Felt>, DeserializationError> {
+    let bytes =
+        hex::decode(value).map_err(|_| invalid_field(field, "is not a valid hex string"))?;
+    if bytes.len() != num_elements * Felt::ELEMENT_BYTES {
+        return Err(invalid_field(
+            field,
+            &format!("must encode {} bytes", num_elements * Felt::ELEMENT_BYTES),
+        ));
+    }
+    Felt::read_batch_from(&mut SliceReader::new(&bytes), num_elements)
+        .map_err(|err| invalid_field(field, &err.to_string()))
+}
+
+/// Decodes a hex string holding a key; the key is read in the same way as keys read from bytes,
+/// and thus, every element of the key must be canonical.
+fn decode_key<K>(value: &str, field: &str) -> Result<K, DeserializationError>
+where
+    K: for<'a> TryFrom<&'a [u8], Error = KeyParseError>,
+{
+    let bytes =
+        hex::decode(value).map_err(|_| invalid_field(field, "is not a valid hex string"))?;
+    K::try_from(bytes.as_slice()).map_err(|err| invalid_field(field, &err.to_string()))
+}
+
+/// Decodes a hex string holding a digest.
+fn decode_digest(value: &str, field: &str) -> Result<Digest, DeserializationError> {
+    // decode_elements() returns exactly the requested number of elements
+    let elements = decode_elements(value, 4, field)?;
+    let elements: [Felt; 4] = elements.try_into().expect("invalid number of elements");
+    Ok(elements.into())
+}
+
+fn map_json_error(err: serde_json::Error) -> DeserializationError {
+    if err.is_eof() {
+        DeserializationError::UnexpectedEOF
+    } else {
+        DeserializationError::InvalidValue(format!("invalid JSON: {}", err))
+    }
+}
+
+fn invalid_field(field: &str, message: &str) -> DeserializationError {
+    DeserializationError::InvalidValue(format!("invalid field '{}': {}", field, message))
+}
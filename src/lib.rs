@@ -1,55 +1,116 @@
 //! This module implements a simple version of Semaphore protocol using STARK proving system.
 //!
 //! The Semaphore protocol has the following properties:
-//! - There is a set of users each possessing a (secret) private key.
-//! - Each user can derive a public key from their private key.
+//! - There is a set of users each possessing a (secret) identity.
+//! - Each user can derive a public key (an identity commitment) from their identity.
 //! - A collection of public keys forms an "access set".
 //!
 //! Once users have formed an access set, they should be able to do the following:
-//! - A user should be able to send a signal on a topic (a topic could be any string).
+//! - A user should be able to send a signal on a topic (a topic could be any string, or a
+//!   structured external nullifier consisting of an application ID, a scope, and an epoch).
 //! - Each signal should have a unique identifier. This identifier is called a nullifier.
-//!   Thus, a combination (private key, topic) should always produce the same nullifier.
-//!   This ensures that the same private key cannot be used to signal more than once on
+//!   Thus, a combination (identity, topic) should always produce the same nullifier.
+//!   This ensures that the same identity cannot be used to signal more than once on
 //!   a given topic.
 //! - Given a signal, it should not be possible to infer which user made it. That is,
 //!   signaling must be private.
 //!
 //! In our concrete setting, the protocol is implemented as follows:
-//! - A secret key is represented by 4 field elements in a 64-bit prime field. This means that
-//!   a secret key is roughly 256-bits long.
-//! - A public key is derived by hashing private key with zero - i.e., hash(priv_key, 0). For
+//! - An identity consists of two secrets: a nullifier secret and a trapdoor. Each secret is
+//!   represented by 4 field elements in a 64-bit prime field. This means that each secret is
+//!   roughly 256-bits long.
+//! - A public key (identity commitment) is derived by hashing the two secrets together, and
+//!   then hashing the result with zero - i.e., hash(hash(nullifier_secret, trapdoor), 0). For
 //!   the hash function we use Rp64_256 (which is an instantiation of Rescue Prime). The output
 //!   of this hash function (the digest) is also 4 field elements in the same field.
 //! - An access set is built by combining public keys into a Merkle tree using the same Rp64_256
 //!   hash function.
-//! - A nullifier is computed by hashing a private key together with a hash of the topic - i.e.:
-//!   hash(priv_key, hash(topic)) using the same Rp64_256 hash function.
+//! - A nullifier is computed by hashing the nullifier secret together with a hash of the topic -
+//!   i.e.: hash(nullifier_secret, hash(topic)) using the same Rp64_256 hash function. The
+//!   trapdoor is never used in nullifiers, and thus, the nullifier secret alone is not enough to
+//!   link nullifiers to a public key.
 //! - To make a signal on a topic, the user first computes a nullifier as described above, and then
 //!   generates a STARK proof attesting that the nullifier was computed correctly, and that the
-//!   user's private key is present in the Merkle tree representing a given access set. Thus,
+//!   user's public key is present in the Merkle tree representing a given access set. Thus,
 //!   a signal is a tuple consisting of (nullifier, STARK proof).
-//! - Given a topic, a root of a Merkle tree representing an access set, and a signal, anyone
-//!   can verify that this signal is indeed a valid signal against this topic by verifying
-//!   the STARK proof.
+//! - A signal carries a message (e.g., a vote). A hash of the message is a public input to the
+//!   STARK proof, and thus, the message cannot be replaced without invalidating the proof.
+//! - A topic may allow up to k signals per user (rate limiting). In this case, the nullifier is
+//!   computed from (nullifier_secret, topic, message_index), and the STARK proof additionally
+//!   attests that the private message index is smaller than k.
+//! - A topic may use slashing. In this case, every signal reveals a point on a line whose
+//!   constant term is the identity, and the STARK proof attests that the point was computed
+//!   correctly. The x coordinate of the point is bound to the whole message hash, and a single
+//!   point reveals nothing about the identity. Two signals with the same nullifier but different
+//!   messages reveal two points on the same line, and thus, the identity of the signaller.
+//! - Given a topic, a root of a Merkle tree representing an access set, a message, and a signal,
+//!   anyone can verify that this signal is indeed a valid signal with this message against this
+//!   topic by verifying the STARK proof.
 
-use std::{convert::TryInto, fmt, ops::Range};
-use winter_utils::{Deserializable, SliceReader};
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    ops::Range,
+    str::FromStr,
+};
+use winter_utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
 use winterfell::{
     crypto::{hashers::Rp64_256 as Rescue, Digest as HasherDigest, Hasher, MerkleTree},
-    math::{fields::f64::BaseElement as Felt, log2, FieldElement, StarkField},
+    math::{fields::f64::BaseElement as Felt, FieldElement, StarkField},
     ProofOptions, Prover, StarkProof, Trace, TraceTable,
 };
 
 mod air;
-use air::{PublicInputs, SemaphoreAir, merge_evil};
+use air::{
+    compute_key_share, compute_nullifier, get_key_share_x, get_root_step, get_trace_length,
+    init_nullifier_state, HASH_CAPACITY, KEY_SHARE_OFFSET, MESSAGE_INDEX_SENTINEL,
+};
+pub use air::{debug_trace, ConstraintFailure, ConstraintKind, PublicInputs, SemaphoreAir};
 
-mod prover;
-use prover::SemaphoreProver;
+mod audit;
+pub use audit::{AccessSetChange, AuditRecord};
+
+mod descriptor;
+pub use descriptor::{AccessSetDescriptor, AccessSetFormat};
+
+mod errors;
+pub use errors::SemaphoreError;
+
+mod incremental;
+pub use incremental::IncrementalAccessSet;
 
+mod json;
+pub use json::JSON_SCHEMA_VERSION;
 
-// mod evil_hash;
-// use crate::evil_hash::Hasher as HasherEvil;
-// use evil_hash::rescue::Rp64_256 as RescueEvil;
+mod policy;
+pub use policy::VerificationPolicy;
+
+mod registry;
+pub use registry::{Group, GroupId, GroupRegistry, GroupSignal};
+
+mod topic;
+use topic::hash_bytes;
+pub use topic::{ExternalNullifier, Topic};
+
+mod verifier;
+pub use verifier::SignalVerifier;
+
+mod store;
+pub use store::{FileNullifierStore, InMemoryNullifierStore, NullifierStore};
+
+mod poll;
+pub use poll::{Ballot, Poll, Tally, Transcript};
+
+mod slashing;
+pub use slashing::{recover_identity_from_double_signal, SlashingEvidence};
+
+mod prover;
+pub use prover::{ProofOptionsBuilder, SecurityPreset, SemaphoreProver};
+
+#[cfg(test)]
+mod tests;
 
 // TYPE ALIASES
 // ================================================================================================
@@ -58,104 +119,305 @@ use prover::SemaphoreProver;
 /// prime field.
 type Digest = <Rescue as Hasher>::Digest;
 
+/// Key share revealed by a signal on a topic which uses slashing.
+///
+/// A key share holds the 8 elements of the identity (the nullifier secret followed by the
+/// trapdoor) evaluated on their share polynomials at a single x coordinate derived from the
+/// message hash (see [SlashingEvidence]).
+pub type KeyShare = [Felt; 8];
+
 // ACCESS SET
 // ================================================================================================
 
 /// Defines an access set of users who can signal on a given topic.
 ///
 /// An access set is a collection of users' public keys which internally are stored in a Merkle
-/// tree. For simplicity, we impose the following restrictions on the size of an access set:
-/// - The number of public keys must be exactly a power of two.
-/// - Base two logarithm of the number of public keys must be one less than a power of two.
+/// tree. An access set can hold any number of public keys; if the number of keys is not a power of
+/// two (or is one), the leaves of the tree are padded with empty leaves up to the next power of
+/// two. An empty leaf is a hash of a domain separation tag which is computed differently from
+/// public keys, and thus, no identity is known to hash into it; this means that a signal cannot
+/// be made for an empty leaf.
 ///
-/// Thus, for example, the following set sizes are allowed: 8, 128, 32768 etc.
-pub struct AccessSet(MerkleTree<Rescue>);
+/// An access set also holds a [VerificationPolicy] which signals must satisfy to be accepted by
+/// [AccessSet::verify_signal()].
+pub struct AccessSet {
+    key_tree: MerkleTree<Rescue>,
+    num_keys: usize,
+    policy: VerificationPolicy,
+}
 
 impl AccessSet {
-    pub fn new(pub_keys: Vec<PubKey>) -> Self {
-        // make sure we got a valid number of public keys
+    /// Returns a new access set built from the provided public keys.
+    ///
+    /// # Errors
+    /// Returns an error if the list of public keys is empty or holds more than 2^32 keys, or if
+    /// any of the keys is the empty leaf or the removed leaf.
+    pub fn new(pub_keys: Vec<PubKey>) -> Result<Self, SemaphoreError> {
+        // make sure we got at least one public key
         let num_keys = pub_keys.len();
-        assert!(
-            num_keys.is_power_of_two(),
-            "number of public keys must be a power of two"
-        );
-        assert!(
-            (log2(num_keys) + 1).is_power_of_two(),
-            "base two logarithm of the number of public keys must be one less than a power of two"
-        );
+        if num_keys == 0 {
+            return Err(SemaphoreError::InvalidSetSize(num_keys));
+        }
+        pub_keys.iter().try_for_each(validate_pub_key)?;
 
         // convert public keys into leaves of a Merkle tree; we do this simply by extracting
         // public keys inner type (which is a Digest) from each key.
-        let leaves = pub_keys.iter().map(|p| p.0).collect::<Vec<_>>();
+        Self::from_leaves(pub_keys.iter().map(|p| p.0).collect())
+    }
 
-        // build a Merkle tree from the public key leaves
-        let key_tree = MerkleTree::new(leaves).unwrap();
-        Self(key_tree)
+    /// Returns this access set with its verification policy replaced by the provided one.
+    pub fn with_policy(mut self, policy: VerificationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the policy which signals must satisfy to be accepted by this access set.
+    pub fn policy(&self) -> &VerificationPolicy {
+        &self.policy
     }
 
     /// Returns the root of this access set.
     pub fn root(&self) -> Digest {
-        *self.0.root()
+        *self.key_tree.root()
+    }
+
+    /// Returns the number of public keys in this access set; this does not include empty leaves,
+    /// but includes slots of removed keys.
+    pub fn num_keys(&self) -> usize {
+        self.num_keys
+    }
+
+    /// Returns the depth of the Merkle tree which holds the keys of this access set.
+    pub fn depth(&self) -> usize {
+        self.key_tree.depth()
+    }
+
+    /// Returns the length of the execution trace of signals made for this access set.
+    ///
+    /// The trace contains two hash cycles for hashing the identity into a public key, and one
+    /// hash cycle for every level of the Merkle tree; the trace is padded with additional hash
+    /// cycles so that its length is a power of two.
+    pub fn trace_length(&self) -> usize {
+        get_trace_length(self.depth())
     }
 
     /// Returns a Merkle path to the key at the specified index.
     ///
     /// The first element in the path will be the public key itself.
-    pub fn get_key_path(&self, key_idx: usize) -> Vec<Digest> {
-        self.0.prove(key_idx).expect("invalid key index")
+    ///
+    /// # Errors
+    /// Returns an error if the key index is not smaller than the number of public keys in this
+    /// access set.
+    pub fn get_key_path(&self, key_idx: usize) -> Result<Vec<Digest>, SemaphoreError> {
+        if key_idx >= self.num_keys {
+            return Err(SemaphoreError::InvalidIndex(key_idx));
+        }
+        self.key_tree
+            .prove(key_idx)
+            .map_err(|_| SemaphoreError::InvalidIndex(key_idx))
     }
 
-    /// Returns a signal of the user with specified private key on the specified topic.
+    /// Removes the public key at the specified index from this access set, and returns an audit
+    /// record of the removal.
+    ///
+    /// The slot of the key is replaced with the removed leaf, for which no signal can be made.
+    /// Indexes of other keys do not change. The tree is rebuilt from its leaves, and thus, this
+    /// takes O(n) hashes; an [IncrementalAccessSet] can be used to remove keys in O(depth).
     ///
-    /// The signal includes a unique nullifier for the combination of (priv_key, topic), as well
-    /// as the proof that the public key for the provided private key exists in this access set.
-    pub fn make_signal(&self, priv_key: &PrivKey, topic: &str) -> Signal {
-        // compute hash of the topic
-        let topic = Rescue::hash(topic.as_bytes());
+    /// # Errors
+    /// Returns an error if the key index is not smaller than the number of public keys in this
+    /// access set, or if the key at this index has already been removed.
+    pub fn remove(&mut self, key_idx: usize) -> Result<AuditRecord, SemaphoreError> {
+        self.update_key(key_idx, get_removed_leaf())
+    }
 
+    /// Replaces the public key at the specified index in this access set with the provided key
+    /// (e.g., to rotate the key of a user), and returns an audit record of the replacement.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The key index is not smaller than the number of public keys in this access set.
+    /// - The key at this index has been removed.
+    /// - The new key is the empty leaf or the removed leaf.
+    pub fn replace(
+        &mut self,
+        key_idx: usize,
+        new_pub_key: PubKey,
+    ) -> Result<AuditRecord, SemaphoreError> {
+        validate_pub_key(&new_pub_key)?;
+        self.update_key(key_idx, new_pub_key.0)
+    }
+
+    /// Returns a signal of the user with specified identity on the specified topic.
+    ///
+    /// The topic can be either a free-form string, or an [ExternalNullifier].
+    ///
+    /// The signal includes a unique nullifier for the combination of (identity, topic), as well
+    /// as the proof that the public key for the provided identity exists in this access set.
+    /// The hash of the message is bound to the proof, and thus, the proof is valid only for the
+    /// provided message. The proof is generated by the provided prover, and thus, its security
+    /// level and size depend on the options the prover was configured with.
+    ///
+    /// For topics which allow multiple signals per user, this is the same as
+    /// [AccessSet::make_rate_limited_signal()] with message index 0.
+    ///
+    /// # Errors
+    /// Returns an error if the public key for the provided identity is not in this access set,
+    /// or if the proof could not be generated.
+    pub fn make_signal(
+        &self,
+        identity: &Identity,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        prover: &SemaphoreProver,
+    ) -> Result<Signal, SemaphoreError> {
+        self.make_rate_limited_signal(identity, topic, message, 0, prover)
+    }
+
+    /// Returns a signal of the user with specified identity on the specified topic, using the
+    /// specified message index.
+    ///
+    /// The nullifier of the signal is unique for the combination of (identity, topic,
+    /// message_index), and the proof attests that the message index is smaller than the message
+    /// limit of the topic; the index itself is not revealed. Thus, a user can make up to k signals
+    /// with distinct nullifiers on a topic with a limit of k, and a [NullifierStore] rejects any
+    /// further signals by the same user on this topic.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The message index is not smaller than the message limit of the topic.
+    /// - The public key for the provided identity is not in this access set.
+    /// - The proof could not be generated.
+    pub fn make_rate_limited_signal(
+        &self,
+        identity: &Identity,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        message_index: u32,
+        prover: &SemaphoreProver,
+    ) -> Result<Signal, SemaphoreError> {
         // get the index of the key in the Merkle tree
-        let pub_key = PubKey::new(priv_key);
-        let key_idx = self
-            .0
-            .leaves()
+        let pub_key = PubKey::new(identity);
+        let key_idx = self.key_tree.leaves()[..self.num_keys]
             .iter()
             .position(|&v| v == pub_key.0)
-            .expect("public key for the provided private key could not be found");
+            .ok_or(SemaphoreError::KeyNotInSet)?;
 
         // get the path to the key from the Merkle tree
-        let key_path = self
-            .0
-            .prove(key_idx)
-            .expect("failed to build a Merkle path for key index");
+        let key_path = self.get_key_path(key_idx)?;
+
+        build_signal(
+            identity,
+            key_idx,
+            &key_path,
+            topic.into(),
+            message,
+            message_index,
+            prover,
+        )
+    }
 
-        // compute the nullifier for this key and topic
-        let nullifier = priv_key.get_nullifier(topic);
+    /// Returns Ok(()) if the provided signal is a valid signal with the specified message on the
+    /// specified topic by someone with a key from this access set.
+    ///
+    /// A proof made for an access set of a different depth, or a proof which does not satisfy the
+    /// verification policy of this access set, is rejected before the proof itself is verified.
+    ///
+    /// Verification uses only the root and the depth of this access set; to verify signals
+    /// without holding the access set, use a [SignalVerifier].
+    pub fn verify_signal(
+        &self,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        signal: Signal,
+    ) -> Result<(), SemaphoreError> {
+        self.verifier().verify_signal(topic, message, signal)
+    }
+
+    /// Verifies the provided signal and records its nullifier in the provided store.
+    ///
+    /// A signal is accepted only if it is valid (as defined by [AccessSet::verify_signal()]), and
+    /// no signal with the same nullifier has been accepted on the same topic before. This ensures
+    /// that every user can signal at most once on a given topic.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The signal is not valid.
+    /// - A signal with the same nullifier has already been recorded in the store for this topic.
+    /// - The store could not be read or updated.
+    pub fn accept_signal<S: NullifierStore>(
+        &self,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        signal: Signal,
+        store: &mut S,
+    ) -> Result<(), SemaphoreError> {
+        self.verifier().accept_signal(topic, message, signal, store)
+    }
 
-        // build the proof asserting that the key is in the access set and that if hashed with
-        // the specified topic it produces a given nullifier.
-        let prover = SemaphoreProver::default();
-        let trace = prover.build_trace(priv_key, key_idx, topic, &key_path);
-        let proof = prover.prove(trace).expect("failed to generate proof");
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
 
-        // return the signal
-        Signal { nullifier, proof }
+    /// Returns a new access set built from the provided non-empty list of leaves; the leaves may
+    /// include removed leaves.
+    ///
+    /// # Errors
+    /// Returns an error if there are more than 2^32 leaves, as the depth of the tree would then
+    /// exceed the maximum tree depth.
+    fn from_leaves(mut leaves: Vec<Digest>) -> Result<Self, SemaphoreError> {
+        let num_keys = leaves.len();
+        if num_keys as u64 > 1u64 << MAX_TREE_DEPTH {
+            return Err(SemaphoreError::InvalidSetSize(num_keys));
+        }
+
+        // pad the leaves with empty leaves; a Merkle tree must have at least two leaves
+        leaves.resize(num_keys.next_power_of_two().max(2), get_empty_leaf());
+
+        // building the tree cannot fail: the number of leaves is a power of two greater than 1
+        let key_tree = MerkleTree::new(leaves).unwrap();
+        Ok(Self {
+            key_tree,
+            num_keys,
+            policy: VerificationPolicy::default(),
+        })
     }
 
-    /// Returns Ok(()) if the provided signal is a valid signal on the specified topic by someone
-    /// with a key from this access set.
-    pub fn verify_signal(&self, topic: &str, signal: Signal) -> Result<(), String> {
-        // create public inputs for proof verification
-        let pub_inputs = PublicInputs {
-            tree_root: self.root(),
-            nullifier: signal.nullifier,
-            topic: Rescue::hash(topic.as_bytes()),
-        };
+    /// Returns a verifier of signals against the root, the depth, and the policy of this access
+    /// set.
+    fn verifier(&self) -> SignalVerifier {
+        // from_leaves() limits access sets to 2^32 keys, and pads the tree to at least two
+        // leaves; thus, the depth is always between 1 and MAX_TREE_DEPTH
+        SignalVerifier::new(self.root(), self.depth(), self.policy.clone())
+            .expect("access set depth is valid")
+    }
 
-        // check if the STARK proof is valid against the above public inputs
-        match winterfell::verify::<SemaphoreAir>(signal.proof, pub_inputs) {
-            Ok(_) => Ok(()),
-            Err(err) => Err(format!("proof verification failed: {}", err)),
+    /// Sets the leaf at the specified index to the provided value, rebuilds the Merkle tree, and
+    /// returns an audit record of the change.
+    fn update_key(
+        &mut self,
+        key_idx: usize,
+        new_leaf: Digest,
+    ) -> Result<AuditRecord, SemaphoreError> {
+        if key_idx >= self.num_keys {
+            return Err(SemaphoreError::InvalidIndex(key_idx));
         }
+        let mut leaves = self.key_tree.leaves().to_vec();
+        let old_leaf = leaves[key_idx];
+        if old_leaf == get_removed_leaf() {
+            return Err(SemaphoreError::KeyRemoved(key_idx));
+        }
+
+        let old_root = self.root();
+        leaves[key_idx] = new_leaf;
+        // the leaves come from the existing tree, and thus, their number is a valid tree size
+        self.key_tree = MerkleTree::new(leaves).unwrap();
+        Ok(AuditRecord::new(
+            key_idx,
+            old_leaf,
+            new_leaf,
+            old_root,
+            self.root(),
+        ))
     }
 }
 
@@ -164,19 +426,154 @@ impl AccessSet {
 
 /// Defines a signal on a topic.
 ///
-/// A signal contains an `nullifier`, which is a unique value for a combination of (private key,
+/// A signal contains an `nullifier`, which is a unique value for a combination of (identity,
 /// topic). The signal also contains a STARK proof which attests that a signal was made by someone
-/// with a private key which was used to compute the nullifier, and that this private key belongs
-/// to a given access set.
+/// with an identity which was used to compute the nullifier, and that the public key of this
+/// identity belongs to a given access set.
+///
+/// For topics which use slashing, the signal also contains a key share of the signaller.
+///
+/// The signal also states the root and the depth of the access set it was made for. The depth
+/// cannot always be recovered from the proof, as traces for several depths are padded to the
+/// same length, and the root tells a verifier which accepts several roots which one to verify the
+/// proof against. Both are checked before the proof is verified.
+///
+/// A signal can be serialized into a versioned binary format (see [Signal::to_bytes()]) to be sent
+/// over the wire or saved to disk.
 #[derive(Debug, Clone)]
 pub struct Signal {
+    pub root: Digest,
+    depth: usize,
     pub nullifier: Digest,
     pub proof: StarkProof,
+    pub key_share: Option<KeyShare>,
+}
+
+impl Signal {
+    /// Returns the depth of the access set this signal was made for.
+    ///
+    /// The depth is set when the signal is made or decoded, and it is always between 1 and 32.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns a byte representation of this signal.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.write_into(&mut result);
+        result
+    }
+
+    /// Returns a signal read from the provided bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes do not represent a valid signal, or if there are bytes left
+    /// after the signal.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SemaphoreError> {
+        let mut source = SliceReader::new(bytes);
+        let signal = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes.into());
+        }
+        Ok(signal)
+    }
+}
+
+impl Serializable for Signal {
+    /// Serializes this signal as follows:
+    /// - Magic header [SIGNAL_MAGIC] (4 bytes), and format version [SIGNAL_FORMAT_VERSION]
+    ///   (1 byte).
+    /// - Depth of the access set (1 byte), and root of the access set (32 bytes).
+    /// - Nullifier (32 bytes).
+    /// - A flag which is 1 if the signal contains a key share, and 0 otherwise (1 byte), followed
+    ///   by the key share if it is present (64 bytes).
+    /// - Proof length (4 bytes), and the proof bytes.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        let proof_bytes = self.proof.to_bytes();
+        target.write_u8_slice(&SIGNAL_MAGIC);
+        target.write_u8(SIGNAL_FORMAT_VERSION);
+        // the depth cannot be changed outside of this crate, and it is always between 1 and 32
+        target.write_u8(self.depth as u8);
+        target.write(self.root);
+        target.write(self.nullifier);
+        match self.key_share {
+            Some(key_share) => {
+                target.write_u8(1);
+                target.write_u8_slice(Felt::elements_as_bytes(&key_share));
+            }
+            None => target.write_u8(0),
+        }
+        target.write_u32(proof_bytes.len() as u32);
+        target.write_u8_slice(&proof_bytes);
+    }
+}
+
+impl Deserializable for Signal {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        if source.read_u8_array::<4>()? != SIGNAL_MAGIC {
+            return Err(DeserializationError::InvalidValue(
+                "invalid signal magic header".to_string(),
+            ));
+        }
+        let version = source.read_u8()?;
+        if version != SIGNAL_FORMAT_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported signal format version {}; expected version {}",
+                version, SIGNAL_FORMAT_VERSION
+            )));
+        }
+
+        let depth = source.read_u8()? as usize;
+        if !(1..=MAX_TREE_DEPTH).contains(&depth) {
+            return Err(DeserializationError::InvalidValue(format!(
+                "access set depth must be between 1 and {}, but was {}",
+                MAX_TREE_DEPTH, depth
+            )));
+        }
+        let root = Digest::read_from(source)?;
+        let nullifier = Digest::read_from(source)?;
+        let key_share = match source.read_u8()? {
+            0 => None,
+            1 => Some(read_key_share(source)?),
+            value => {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "invalid key share flag {}",
+                    value
+                )))
+            }
+        };
+
+        // check the proof length before reading the proof so that a malformed length cannot
+        // cause a large allocation
+        let proof_len = source.read_u32()? as usize;
+        if proof_len > MAX_SIGNAL_PROOF_SIZE {
+            return Err(DeserializationError::InvalidValue(format!(
+                "signal proof size of {} bytes exceeds the maximum of {} bytes",
+                proof_len, MAX_SIGNAL_PROOF_SIZE
+            )));
+        }
+        let proof = StarkProof::from_bytes(&source.read_u8_vec(proof_len)?)?;
+
+        Ok(Self {
+            root,
+            depth,
+            nullifier,
+            proof,
+            key_share,
+        })
+    }
 }
 
 impl fmt::Display for Signal {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Nullifier: {}", hex::encode(self.nullifier.as_bytes()))?;
+        if let Some(key_share) = self.key_share {
+            writeln!(
+                f,
+                "Key share: {}",
+                hex::encode(Felt::elements_as_bytes(&key_share))
+            )?;
+        }
         writeln!(
             f,
             "Proof size: {:.1} KB",
@@ -193,17 +590,28 @@ impl fmt::Display for Signal {
 // PUBLIC KEY
 // ================================================================================================
 
-/// Defines a public key for a given private key.
+/// Defines a public key for a given identity.
 ///
-/// A public key is simply a hash of a private key.
+/// A public key is an identity commitment, which is a hash of the hash of the identity secrets.
 #[derive(Debug)]
 pub struct PubKey(Digest);
 
 impl PubKey {
-    /// Returns a [PubKey] instantiated from the provided private key.
+    /// Returns a [PubKey] instantiated from the provided identity.
+    ///
+    /// The key is computed as hash(hash(nullifier_secret, trapdoor), 0).
+    pub fn new(identity: &Identity) -> Self {
+        let secret_hash = identity.secret_hash();
+        Self(Rescue::merge(&[secret_hash, [Felt::ZERO; 4].into()]))
+    }
+
+    /// Returns a legacy [PubKey] instantiated from the provided private key.
     ///
-    /// The key is computed simply as hash(priv_key, 0).
-    pub fn new(priv_key: &PrivKey) -> Self {
+    /// The key is computed simply as hash(priv_key, 0). Such keys cannot be used to make signals;
+    /// this can be used to find the public key of a user in an access set built from legacy keys,
+    /// so that it can be replaced by the public key of the migrated identity (see
+    /// [Identity::from_priv_key()]).
+    pub fn from_priv_key(priv_key: &PrivKey) -> Self {
         let priv_key_elements: [Felt; 4] = priv_key.elements();
         let priv_key_hash = Rescue::merge(&[priv_key_elements.into(), [Felt::ZERO; 4].into()]);
         Self(priv_key_hash)
@@ -211,16 +619,12 @@ impl PubKey {
 
     /// Returns a [PubKey] parsed from the provided string.
     ///
-    /// # Panics
-    /// Panics if the string does not represent a valid encoding of a public key.
-    pub fn parse(key: &str) -> Self {
-        let key_bytes = hex::decode(key).expect("invalid key encoding");
-        let mut key_reader = SliceReader::new(&key_bytes);
-        let key_elements = Felt::read_batch_from(&mut key_reader, 4).expect("invalid key encoding");
-        let key_array: [Felt; 4] = key_elements
-            .try_into()
-            .expect("failed to convert vector to array");
-        Self(key_array.into())
+    /// This is the same as parsing the string via [FromStr], but returns a [SemaphoreError].
+    ///
+    /// # Errors
+    /// Returns an error if the string does not represent a valid encoding of a public key.
+    pub fn parse(key: &str) -> Result<Self, SemaphoreError> {
+        key.parse().map_err(SemaphoreError::InvalidKey)
     }
 
     /// Returns elements which make up this public key.
@@ -235,13 +639,153 @@ impl fmt::Display for PubKey {
     }
 }
 
+impl FromStr for PubKey {
+    type Err = KeyParseError;
+
+    /// Parses a public key from a hex encoding of 32 bytes.
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let key_bytes = hex::decode(key).map_err(|_| KeyParseError::InvalidHex)?;
+        Self::try_from(key_bytes.as_slice())
+    }
+}
+
+impl TryFrom<&[u8]> for PubKey {
+    type Error = KeyParseError;
+
+    /// Reads a public key from 32 bytes holding 4 field elements in little-endian byte order.
+    fn try_from(key_bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(read_key_elements(key_bytes)?.into()))
+    }
+}
+
+// IDENTITY
+// ================================================================================================
+
+/// Defines an identity of a user.
+///
+/// An identity consists of two secrets, each of which is 4 field elements in a 64-bit prime
+/// field:
+/// - The nullifier secret, which is used to compute nullifiers.
+/// - The trapdoor, which is used only to compute the public key of the identity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    nullifier_secret: [Felt; 4],
+    trapdoor: [Felt; 4],
+}
+
+impl Identity {
+    /// Returns an [Identity] instantiated with the provided secrets.
+    pub fn new(nullifier_secret: [Felt; 4], trapdoor: [Felt; 4]) -> Self {
+        Self {
+            nullifier_secret,
+            trapdoor,
+        }
+    }
+
+    /// Returns an [Identity] migrated from the provided legacy private key and trapdoor.
+    ///
+    /// The private key becomes the nullifier secret, and thus, nullifiers of the migrated identity
+    /// are the same as nullifiers of the private key; this way, a user cannot signal again on a
+    /// topic on which they already signalled before the migration. The public key of the identity
+    /// is different from the legacy public key (see [PubKey::from_priv_key()]), and thus, access
+    /// sets must be rebuilt with the public keys of migrated identities.
+    ///
+    /// The trapdoor must be a fresh secret (e.g., sampled at random) rather than a value derived
+    /// from the private key; otherwise, anyone who learns the nullifier secret would also learn
+    /// the trapdoor.
+    pub fn from_priv_key(priv_key: &PrivKey, trapdoor: [Felt; 4]) -> Self {
+        Self::new(priv_key.elements(), trapdoor)
+    }
+
+    /// Returns an [Identity] parsed from the provided string; the string is expected to encode
+    /// the nullifier secret followed by the trapdoor.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a hex encoding of 64 bytes, or if any of the secrets
+    /// is not a canonical encoding of field elements.
+    pub fn parse(identity: &str) -> Result<Self, SemaphoreError> {
+        let identity_bytes = hex::decode(identity).map_err(|_| {
+            SemaphoreError::InvalidIdentity("identity is not a valid hex string".to_string())
+        })?;
+        if identity_bytes.len() != 2 * KEY_BYTES {
+            return Err(SemaphoreError::InvalidIdentity(format!(
+                "identity must consist of {} bytes, but was {} bytes",
+                2 * KEY_BYTES,
+                identity_bytes.len()
+            )));
+        }
+        let (nullifier_secret, trapdoor) = identity_bytes.split_at(KEY_BYTES);
+        let nullifier_secret = read_key_elements(nullifier_secret)
+            .map_err(|err| SemaphoreError::InvalidIdentity(format!("nullifier secret: {}", err)))?;
+        let trapdoor = read_key_elements(trapdoor)
+            .map_err(|err| SemaphoreError::InvalidIdentity(format!("trapdoor: {}", err)))?;
+        Ok(Self::new(nullifier_secret, trapdoor))
+    }
+
+    /// Returns the public key of this identity.
+    pub fn pub_key(&self) -> PubKey {
+        PubKey::new(self)
+    }
+
+    /// Creates a nullifier for the provided topic against this identity.
+    ///
+    /// A nullifier is computed simply as hash(nullifier_secret, topic).
+    pub fn get_nullifier(&self, topic: Digest) -> Digest {
+        self.get_nullifier_at(topic, 0)
+    }
+
+    /// Creates a nullifier for the provided topic and message index against this identity.
+    ///
+    /// The message index is placed into the capacity of the hash state, and thus, for message
+    /// index 0, this is the same as [Identity::get_nullifier()].
+    pub fn get_nullifier_at(&self, topic: Digest, message_index: u32) -> Digest {
+        compute_nullifier(self.nullifier_secret, topic.into(), message_index)
+    }
+
+    /// Returns the nullifier secret of this identity.
+    pub fn nullifier_secret(&self) -> [Felt; 4] {
+        self.nullifier_secret
+    }
+
+    /// Returns the trapdoor of this identity.
+    pub fn trapdoor(&self) -> [Felt; 4] {
+        self.trapdoor
+    }
+
+    /// Returns elements which make up this identity; these are the elements of the nullifier
+    /// secret followed by the elements of the trapdoor.
+    pub fn elements(&self) -> [Felt; 8] {
+        let mut result = [Felt::ZERO; 8];
+        result[..4].copy_from_slice(&self.nullifier_secret);
+        result[4..].copy_from_slice(&self.trapdoor);
+        result
+    }
+
+    /// Returns hash(nullifier_secret, trapdoor).
+    fn secret_hash(&self) -> Digest {
+        Rescue::merge(&[self.nullifier_secret.into(), self.trapdoor.into()])
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            hex::encode(Felt::elements_as_bytes(&self.elements()))
+        )
+    }
+}
+
 // PRIVATE KEY
 // ================================================================================================
 
-/// Defines a private key of a user.
+/// Defines a legacy private key of a user.
 ///
 /// A private key is simply 4 field elements in a 64-bit prime field, which means that a key is
-/// roughly 256 bits in size.
+/// roughly 256 bits in size. Private keys were used both to derive public keys and to compute
+/// nullifiers; they can no longer be used to make signals, and must be migrated to an [Identity]
+/// via [Identity::from_priv_key()].
 #[derive(Debug)]
 pub struct PrivKey([Felt; 4]);
 
@@ -253,27 +797,12 @@ impl PrivKey {
 
     /// Returns a [PrivKey] parsed from the provided string.
     ///
-    /// # Panics
-    /// Panics if the string does not represent a valid encoding of a private key.
-    pub fn parse(key: &str) -> Self {
-        let key_bytes = hex::decode(key).expect("invalid key encoding");
-        let mut key_reader = SliceReader::new(&key_bytes);
-        let key_elements = Felt::read_batch_from(&mut key_reader, 4).expect("invalid key encoding");
-        Self(
-            key_elements
-                .try_into()
-                .expect("failed to convert vector to array"),
-        )
-    }
-
-    /// Creates a nullifier for the provided topic against this private key.
+    /// This is the same as parsing the string via [FromStr], but returns a [SemaphoreError].
     ///
-    /// A nullifier is computed simply as hash(key, topic).
-    pub fn get_nullifier(&self, topic: Digest) -> Digest {
-        let key: Digest = self.0.into();
-        // Rescue::merge(&[key, topic])
-        merge_evil(&[key, topic])
-        // RescueEvil::merge(&[key, topic])
+    /// # Errors
+    /// Returns an error if the string does not represent a valid encoding of a private key.
+    pub fn parse(key: &str) -> Result<Self, SemaphoreError> {
+        key.parse().map_err(SemaphoreError::InvalidKey)
     }
 
     /// Returns elements which make up this private key.
@@ -288,9 +817,69 @@ impl fmt::Display for PrivKey {
     }
 }
 
+impl FromStr for PrivKey {
+    type Err = KeyParseError;
+
+    /// Parses a private key from a hex encoding of 32 bytes.
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let key_bytes = hex::decode(key).map_err(|_| KeyParseError::InvalidHex)?;
+        Self::try_from(key_bytes.as_slice())
+    }
+}
+
+impl TryFrom<&[u8]> for PrivKey {
+    type Error = KeyParseError;
+
+    /// Reads a private key from 32 bytes holding 4 field elements in little-endian byte order.
+    fn try_from(key_bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(read_key_elements(key_bytes)?))
+    }
+}
+
+// KEY PARSE ERROR
+// ================================================================================================
+
+/// Defines errors which can occur when a key is parsed from a string or from bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyParseError {
+    /// The string is not a valid hex encoding.
+    InvalidHex,
+    /// The key does not consist of exactly 32 bytes; contains the actual number of bytes.
+    InvalidLength(usize),
+    /// The element at the specified index is greater than or equal to the field modulus, and
+    /// thus, it is not a canonical encoding of a field element.
+    NonCanonicalElement(usize),
+}
+
+impl fmt::Display for KeyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHex => write!(f, "key is not a valid hex string"),
+            Self::InvalidLength(len) => write!(
+                f,
+                "key must consist of {} bytes, but was {} bytes",
+                KEY_BYTES, len
+            ),
+            Self::NonCanonicalElement(index) => write!(
+                f,
+                "key element {} is greater than or equal to the field modulus",
+                index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeyParseError {}
+
 // CONSTANTS
 // ================================================================================================
 
+/// Domain separation tag from which the empty leaf of access set Merkle trees is derived.
+const EMPTY_LEAF_TAG: &[u8] = b"semaphore.access_set.empty_leaf";
+
+/// Domain separation tag from which the leaf replacing removed public keys is derived.
+const REMOVED_LEAF_TAG: &[u8] = b"semaphore.access_set.removed_leaf";
+
 /// Number of rounds for Rescue Prime hash function; this is equal to 7.
 const NUM_HASH_ROUNDS: usize = Rescue::NUM_ROUNDS;
 
@@ -298,12 +887,173 @@ const NUM_HASH_ROUNDS: usize = Rescue::NUM_ROUNDS;
 /// to 8.
 const HASH_CYCLE_LEN: usize = NUM_HASH_ROUNDS.next_power_of_two();
 
-/// Number of columns in the execution trace.
-const TRACE_WIDTH: usize = 25;
+/// Number of columns in the execution trace; the last 8 columns hold the key share.
+const TRACE_WIDTH: usize = 36;
+
+/// Number of bytes in an encoding of a public or a private key; this is equal to 32.
+const KEY_BYTES: usize = 4 * Felt::ELEMENT_BYTES;
+
+/// Maximum depth of an access set Merkle tree; an access set can hold up to 2^32 keys.
+const MAX_TREE_DEPTH: usize = 32;
+
+/// Number of bits in the range checks of a message index.
+///
+/// A valid message index is smaller than both 2^15 and the message limit of the topic. The AIR
+/// enforces this by decomposing the index and (message_limit - 1 - index) into 15 bits each:
+/// columns 26 and 27 start with these values plus a sentinel bit 2^15 and are shifted right by one
+/// bit per step, and both columns are asserted to equal the sentinel (ONE) at step 15. Thus, the
+/// decomposition spans the first 16 rows, which fit into the shortest possible execution trace.
+const MESSAGE_INDEX_BITS: usize = 15;
+
+/// Maximum number of signals every user can make on a single topic; this is equal to 2^15.
+pub const MAX_MESSAGE_LIMIT: u32 = 1 << MESSAGE_INDEX_BITS;
+
+/// Magic header which starts every serialized signal.
+pub const SIGNAL_MAGIC: [u8; 4] = *b"SMPS";
+
+/// Version of the signal serialization format written by [Signal::to_bytes()].
+pub const SIGNAL_FORMAT_VERSION: u8 = 1;
+
+/// Magic header which starts every serialized access set descriptor.
+pub const ACCESS_SET_MAGIC: [u8; 4] = *b"SMPA";
+
+/// Version of the access set serialization format written by [AccessSetDescriptor::to_bytes()].
+pub const ACCESS_SET_FORMAT_VERSION: u8 = 1;
+
+/// Magic header which starts every serialized poll transcript.
+pub const TRANSCRIPT_MAGIC: [u8; 4] = *b"SMPT";
+
+/// Version of the transcript serialization format written by [Transcript::to_bytes()].
+pub const TRANSCRIPT_FORMAT_VERSION: u8 = 1;
+
+/// Maximum size of a proof in a serialized signal, in bytes; this is equal to 1 MB.
+///
+/// Signals with larger proofs cannot be deserialized. Note that proofs accepted by the default
+/// [VerificationPolicy] are much smaller than this.
+pub const MAX_SIGNAL_PROOF_SIZE: usize = 1024 * 1024;
 
 // UTILITIES
 // ================================================================================================
 
+/// Reads a key share from the provided source; the key share is expected to be encoded as 8
+/// field elements.
+fn read_key_share<R: ByteReader>(source: &mut R) -> Result<KeyShare, DeserializationError> {
+    let mut key_share = [Felt::ZERO; 8];
+    for value in key_share.iter_mut() {
+        *value = Felt::read_from(source)?;
+    }
+    Ok(key_share)
+}
+
+/// Reads 4 field elements from the provided key bytes, making sure that the bytes consist of
+/// exactly 4 canonical encodings of field elements.
+fn read_key_elements(key_bytes: &[u8]) -> Result<[Felt; 4], KeyParseError> {
+    if key_bytes.len() != KEY_BYTES {
+        return Err(KeyParseError::InvalidLength(key_bytes.len()));
+    }
+
+    let mut elements = [Felt::ZERO; 4];
+    for (i, chunk) in key_bytes.chunks(Felt::ELEMENT_BYTES).enumerate() {
+        // the length of the key is checked above, so every chunk holds exactly 8 bytes
+        let value = u64::from_le_bytes(chunk.try_into().expect("invalid chunk length"));
+        if value >= Felt::MODULUS {
+            return Err(KeyParseError::NonCanonicalElement(i));
+        }
+        elements[i] = Felt::new(value);
+    }
+    Ok(elements)
+}
+
+/// Returns the leaf which is used to pad access set Merkle trees.
+///
+/// The leaf is computed as a hash of [EMPTY_LEAF_TAG] which consists of 5 field elements, while
+/// public keys are computed by merging two digests (i.e., hashing 8 field elements). The number
+/// of hashed elements is a part of the initial hash state, and thus, an identity which hashes
+/// into the empty leaf would require a preimage of the leaf under a different hash function.
+fn get_empty_leaf() -> Digest {
+    hash_bytes(EMPTY_LEAF_TAG)
+}
+
+/// Returns the leaf which replaces public keys removed from access sets.
+///
+/// Similarly to the empty leaf, the leaf is computed as a hash of 5 field elements, and thus, no
+/// identity is known to hash into it. A separate leaf is used so that removed slots can be told
+/// apart from slots which never held a public key.
+fn get_removed_leaf() -> Digest {
+    hash_bytes(REMOVED_LEAF_TAG)
+}
+
+/// Makes sure the provided public key is not one of the leaves reserved for access set slots
+/// which do not hold a public key.
+///
+/// # Errors
+/// Returns an error if the key is the empty leaf or the removed leaf.
+fn validate_pub_key(pub_key: &PubKey) -> Result<(), SemaphoreError> {
+    if pub_key.0 == get_empty_leaf() || pub_key.0 == get_removed_leaf() {
+        return Err(SemaphoreError::InvalidPubKey);
+    }
+    Ok(())
+}
+
+/// Returns a signal of the user with specified identity on the specified topic, using the
+/// provided index and Merkle path of the user's public key.
+///
+/// # Errors
+/// Returns an error if the message index is not smaller than the message limit of the topic, or
+/// if the proof could not be generated.
+fn build_signal(
+    identity: &Identity,
+    key_idx: usize,
+    key_path: &[Digest],
+    topic: Topic,
+    message: &[u8],
+    message_index: u32,
+    prover: &SemaphoreProver,
+) -> Result<Signal, SemaphoreError> {
+    if message_index >= topic.message_limit() {
+        return Err(SemaphoreError::InvalidMessageIndex {
+            index: message_index,
+            limit: topic.message_limit(),
+        });
+    }
+
+    // compute the hash of the message
+    let message = hash_bytes(message);
+
+    // compute the nullifier for this identity, topic, and message index; if the topic uses
+    // slashing, also compute the key share at the point defined by the message
+    let nullifier = identity.get_nullifier_at(topic.digest(), message_index);
+    let key_share = if topic.slashing() {
+        Some(compute_key_share(
+            identity.elements(),
+            topic.digest().into(),
+            message_index,
+            message.into(),
+        ))
+    } else {
+        None
+    };
+
+    // build the proof asserting that the public key is in the access set and that if the
+    // nullifier secret is hashed with the specified topic and message index it produces a
+    // given nullifier.
+    let prover = prover.clone().with_slashing(topic.slashing());
+    let trace = prover.build_trace(identity, key_idx, topic, message_index, message, key_path)?;
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    let proof = prover
+        .prove(trace)
+        .map_err(SemaphoreError::ProofGeneration)?;
+
+    // return the signal
+    Ok(Signal {
+        root: pub_inputs.tree_root,
+        depth: pub_inputs.tree_depth,
+        nullifier,
+        proof,
+        key_share,
+    })
+}
+
 /// Prints out an execution trace.
 pub fn print_trace(
     trace: &TraceTable<Felt>,
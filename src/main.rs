@@ -1,13 +1,15 @@
 use log::debug;
 use prompt::{puzzle, welcome};
-use semaphore::{AccessSet, PrivKey, PubKey};
+use rand_utils::rand_array;
+use semaphore::{AccessSet, Identity, PrivKey, PubKey, SemaphoreProver};
 use std::{io::Write, time::Instant};
 use winter_utils::Serializable;
 
 // DATA
 // ================================================================================================
 
-/// Public keys of users in the access set.
+/// Public keys of users in the access set; our public key is a legacy key derived directly from
+/// our private key.
 const PUB_KEYS: [&str; 8] = [
     "04f6d8d05f52012c0a705c1e0dcb1ff64ba0842c8c14f1f0f18e95254bdcfbea",
     "af84cf58cb71709c5a94750e69f9cbad0244d6c8e437f4e822c58f0c45c69ea0",
@@ -25,6 +27,9 @@ const MY_PRIV_KEY: &str = "86475af21e4445b71bfa496416ee2d0765946bd3a854a77fe07db
 /// A topic on which we'll send a signal
 const TOPIC: &str = "The Winter is Coming...";
 
+/// A message which we'll send in the signal
+const MESSAGE: &[u8] = b"Yes";
+
 // SEMAPHORE TESTER
 // ================================================================================================
 
@@ -38,24 +43,37 @@ pub fn main() {
     welcome();
     puzzle(PUZZLE_DESCRIPTION);
 
-    // build an access set from public keys
+    // parse our private key, and migrate it to an identity with a fresh random trapdoor; the
+    // nullifiers of the identity are the same as the nullifiers of the private key
+    let my_key = PrivKey::parse(MY_PRIV_KEY).expect("failed to parse private key");
+    let my_identity = Identity::from_priv_key(&my_key, rand_array());
+
+    // build an access set from public keys, replacing our legacy public key with the public key
+    // of the migrated identity
+    let my_legacy_pub_key = PubKey::from_priv_key(&my_key).to_string();
     let access_set = AccessSet::new(
         PUB_KEYS
             .iter()
-            .map(|&k| PubKey::parse(k))
+            .map(|&k| {
+                if k == my_legacy_pub_key {
+                    my_identity.pub_key()
+                } else {
+                    PubKey::parse(k).expect("failed to parse public key")
+                }
+            })
             .collect::<Vec<_>>(),
-    );
-
-    // parse our private key
-    let my_key = PrivKey::parse(MY_PRIV_KEY);
+    )
+    .expect("failed to build access set");
 
     debug!("============================================================");
 
-    // create a signal using this private key on some topic; this also includes building a STARK
-    // proof attesting that the private key is in the access set, and that the nullifier contained
-    // in the signal was built correctly.
+    // create a signal using this identity on some topic; this also includes building a STARK
+    // proof attesting that the public key of the identity is in the access set, and that the
+    // nullifier contained in the signal was built correctly.
     let now = Instant::now();
-    let signal = access_set.make_signal(&my_key, TOPIC);
+    let signal = access_set
+        .make_signal(&my_identity, TOPIC, MESSAGE, &SemaphoreProver::default())
+        .expect("failed to create signal");
     debug!(
         "---------------------\nSignal created in {} ms",
         now.elapsed().as_millis()
@@ -65,9 +83,9 @@ pub fn main() {
     debug!("{}", signal);
     debug!("---------------------");
 
-    // the signal should be valid against this topic
+    // the signal should be valid against this topic and message
     let now = Instant::now();
-    match access_set.verify_signal(TOPIC, signal.clone()) {
+    match access_set.verify_signal(TOPIC, MESSAGE, signal.clone()) {
         Ok(_) => debug!(
             "Signal verified in {:.1} ms",
             now.elapsed().as_micros() as f64 / 1000f64
@@ -76,7 +94,9 @@ pub fn main() {
     }
     debug!("============================================================");
 
-    assert_ne!(
+    // with the nullifier capacity pinned by the AIR, the only valid nullifier for our identity on
+    // this topic is hash(nullifier_secret, hash(topic)), where the nullifier secret is our key
+    assert_eq!(
         signal.nullifier.to_bytes(),
         hex::decode("fa9f5e2287b26f5fc91643a65ecfebbf308c6230283cd5c2a6a57ffe8a60e19d").unwrap()
     );
@@ -0,0 +1,84 @@
+use super::SemaphoreError;
+use winterfell::{FieldExtension, HashFunction, StarkProof};
+
+// VERIFICATION POLICY
+// ================================================================================================
+
+/// Defines requirements which a STARK proof contained in a signal must satisfy before the proof
+/// is verified.
+///
+/// Proof options (e.g., number of queries, blowup factor) are chosen by the prover and are
+/// embedded into the proof. Without a policy, a signaller could choose options which result in
+/// a proof with a very low security level (e.g., a single query), and such a proof could be
+/// forged with little effort.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationPolicy {
+    /// Minimum conjectured security level of a proof, in bits.
+    pub min_security_bits: u32,
+    /// Hash functions which a prover is allowed to use for commitments.
+    pub hash_fns: Vec<HashFunction>,
+    /// Field extensions which a prover is allowed to use for the composition polynomial.
+    pub field_extensions: Vec<FieldExtension>,
+    /// Maximum size of a serialized proof, in bytes.
+    pub max_proof_size: usize,
+}
+
+impl VerificationPolicy {
+    /// Returns Ok(()) if the provided proof satisfies this policy.
+    ///
+    /// This check is cheap compared to the full proof verification, and thus, it should be done
+    /// before the proof is verified.
+    ///
+    /// # Errors
+    /// Returns [SemaphoreError::Policy] describing the first requirement the proof violates.
+    pub fn check(&self, proof: &StarkProof) -> Result<(), SemaphoreError> {
+        let options = proof.options();
+
+        if !self.hash_fns.contains(&options.hash_fn()) {
+            return Err(SemaphoreError::Policy(format!(
+                "hash function {:?} is not allowed",
+                options.hash_fn()
+            )));
+        }
+
+        if !self.field_extensions.contains(&options.field_extension()) {
+            return Err(SemaphoreError::Policy(format!(
+                "field extension {:?} is not allowed",
+                options.field_extension()
+            )));
+        }
+
+        let security_level = proof.security_level(true);
+        if security_level < self.min_security_bits {
+            return Err(SemaphoreError::Policy(format!(
+                "security level of {} bits is below the minimum of {} bits",
+                security_level, self.min_security_bits
+            )));
+        }
+
+        let proof_size = proof.to_bytes().len();
+        if proof_size > self.max_proof_size {
+            return Err(SemaphoreError::Policy(format!(
+                "proof size of {} bytes exceeds the maximum of {} bytes",
+                proof_size, self.max_proof_size
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for VerificationPolicy {
+    /// Returns a policy which accepts proofs with at least 90 bits of conjectured security,
+    /// committed to using a 256-bit hash function, and not exceeding 256 KB in size.
+    ///
+    /// Proofs generated by the default prover satisfy this policy.
+    fn default() -> Self {
+        Self {
+            min_security_bits: 90,
+            hash_fns: vec![HashFunction::Blake3_256, HashFunction::Sha3_256],
+            field_extensions: vec![FieldExtension::Quadratic, FieldExtension::Cubic],
+            max_proof_size: 256 * 1024,
+        }
+    }
+}
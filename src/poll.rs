@@ -0,0 +1,477 @@
+use super::{
+    read_key_share, AccessSet, Digest, Felt, FieldElement, Identity, InMemoryNullifierStore,
+    SemaphoreError, SemaphoreProver, Signal, SignalVerifier, Topic, VerificationPolicy,
+    MAX_SIGNAL_PROOF_SIZE, MAX_TREE_DEPTH, TRANSCRIPT_FORMAT_VERSION, TRANSCRIPT_MAGIC,
+};
+use std::{collections::HashSet, fmt};
+use winter_utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+use winterfell::StarkProof;
+
+// POLL
+// ================================================================================================
+
+/// Defines an anonymous poll among users of an access set.
+///
+/// A vote in a poll is a signal on the topic of the poll, where the message of the signal is the
+/// label of the chosen option. Thus, every user can vote at most once, and a vote cannot be moved
+/// to a different option by anyone relaying it.
+///
+/// All accepted votes are recorded, and can be exported as a [Transcript] which anyone can
+/// re-verify without access to the public keys in the access set.
+pub struct Poll {
+    root: Digest,
+    depth: usize,
+    topic: Topic,
+    options: Vec<String>,
+    verifier: SignalVerifier,
+    nullifiers: InMemoryNullifierStore,
+    ballots: Vec<Ballot>,
+}
+
+impl Poll {
+    /// Returns a new poll among users of the provided access set on the specified topic.
+    ///
+    /// Votes are verified against the verification policy of the access set.
+    ///
+    /// # Errors
+    /// Returns an error if the list of options is empty, if it contains duplicate options, or if
+    /// the topic allows more than one signal per user.
+    pub fn new(
+        access_set: &AccessSet,
+        topic: impl Into<Topic>,
+        options: Vec<String>,
+    ) -> Result<Self, SemaphoreError> {
+        Self::from_root(
+            access_set.root(),
+            access_set.depth(),
+            topic.into(),
+            options,
+            access_set.policy().clone(),
+        )
+    }
+
+    /// Returns a new poll among users of an access set with the specified root and depth.
+    ///
+    /// # Errors
+    /// Returns an error if the depth is not between 1 and 32, if the list of options is empty, if
+    /// it contains duplicate options, or if the topic allows more than one signal per user.
+    pub fn from_root(
+        root: Digest,
+        depth: usize,
+        topic: Topic,
+        options: Vec<String>,
+        policy: VerificationPolicy,
+    ) -> Result<Self, SemaphoreError> {
+        let verifier = SignalVerifier::new(root, depth, policy)?;
+        validate_options(&options).map_err(SemaphoreError::InvalidPoll)?;
+        if topic.message_limit() != 1 {
+            return Err(SemaphoreError::InvalidPoll(format!(
+                "a poll topic must allow a single signal per user, but allows {}",
+                topic.message_limit()
+            )));
+        }
+
+        Ok(Self {
+            root,
+            depth,
+            topic,
+            options,
+            verifier,
+            nullifiers: InMemoryNullifierStore::new(),
+            ballots: Vec::new(),
+        })
+    }
+
+    /// Returns the topic of this poll.
+    pub fn topic(&self) -> Topic {
+        self.topic
+    }
+
+    /// Returns the options of this poll.
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// Returns a vote of the user with the specified identity for the option at the specified
+    /// index.
+    ///
+    /// # Errors
+    /// Returns an error if the option index is out of bounds, if the public key for the provided
+    /// identity is not in the access set, or if the proof could not be generated.
+    pub fn make_vote(
+        &self,
+        access_set: &AccessSet,
+        identity: &Identity,
+        option: usize,
+        prover: &SemaphoreProver,
+    ) -> Result<Signal, SemaphoreError> {
+        let message = self
+            .options
+            .get(option)
+            .ok_or(SemaphoreError::InvalidOption(option))?;
+        access_set.make_signal(identity, self.topic, message.as_bytes(), prover)
+    }
+
+    /// Verifies the provided vote for the option at the specified index and records it.
+    ///
+    /// # Errors
+    /// Returns an error if the option index is out of bounds, if the signal is not a valid vote
+    /// for this option, or if a vote with the same nullifier has already been recorded.
+    pub fn accept_vote(&mut self, option: usize, signal: Signal) -> Result<(), SemaphoreError> {
+        let message = self
+            .options
+            .get(option)
+            .ok_or(SemaphoreError::InvalidOption(option))?;
+
+        self.verifier.accept_signal(
+            self.topic,
+            message.as_bytes(),
+            signal.clone(),
+            &mut self.nullifiers,
+        )?;
+
+        self.ballots.push(Ballot { option, signal });
+        Ok(())
+    }
+
+    /// Returns the number of votes for every option of this poll.
+    pub fn tally(&self) -> Tally {
+        let mut counts = vec![0; self.options.len()];
+        for ballot in self.ballots.iter() {
+            counts[ballot.option] += 1;
+        }
+        Tally {
+            options: self.options.clone(),
+            counts,
+        }
+    }
+
+    /// Returns a transcript of this poll which contains all accepted votes.
+    pub fn transcript(&self) -> Transcript {
+        Transcript {
+            root: self.root,
+            depth: self.depth,
+            topic: self.topic,
+            options: self.options.clone(),
+            ballots: self.ballots.clone(),
+        }
+    }
+}
+
+// TALLY
+// ================================================================================================
+
+/// Defines the result of a poll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tally {
+    pub options: Vec<String>,
+    /// Number of votes for every option, in the same order as the options.
+    pub counts: Vec<usize>,
+}
+
+impl Tally {
+    /// Returns the total number of votes.
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (option, count) in self.options.iter().zip(self.counts.iter()) {
+            writeln!(f, "{}: {}", option, count)?;
+        }
+        write!(f, "Total: {}", self.total())
+    }
+}
+
+// TRANSCRIPT
+// ================================================================================================
+
+/// A vote accepted by a poll.
+#[derive(Debug, Clone)]
+pub struct Ballot {
+    /// Index of the chosen option.
+    pub option: usize,
+    pub signal: Signal,
+}
+
+/// Defines a record of a poll which can be re-verified offline.
+///
+/// A transcript contains everything needed to re-verify all votes: the root and the depth of the
+/// access set Merkle tree, the topic, the options, and all accepted votes.
+/// The root, the depth, and the topic recorded in a transcript are not trusted: a transcript is
+/// verified against the root, the depth, and the topic of the poll the verifier expects.
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    pub root: Digest,
+    pub depth: usize,
+    pub topic: Topic,
+    pub options: Vec<String>,
+    pub ballots: Vec<Ballot>,
+}
+
+impl Transcript {
+    /// Re-verifies all votes in this transcript against the access set with the provided root and
+    /// depth, the provided topic, and the provided policy, and returns the resulting tally.
+    ///
+    /// The root, the depth, and the topic recorded in the transcript are only checked against the
+    /// provided ones; otherwise, anyone could build an access set of their own keys and produce
+    /// a transcript of a poll among these keys which verifies.
+    ///
+    /// # Errors
+    /// Returns an error if the transcript was recorded for a different root, depth, or topic, or
+    /// if its options or its topic do not describe a valid poll
+    /// ([SemaphoreError::InvalidTranscript]), if any of the votes is invalid, or if the transcript
+    /// contains multiple votes by the same user.
+    pub fn verify(
+        &self,
+        root: Digest,
+        depth: usize,
+        topic: Topic,
+        policy: &VerificationPolicy,
+    ) -> Result<Tally, SemaphoreError> {
+        if self.root != root {
+            return Err(SemaphoreError::InvalidTranscript(Box::new(
+                SemaphoreError::RootMismatch {
+                    expected: root,
+                    actual: self.root,
+                },
+            )));
+        }
+        if self.depth != depth {
+            return Err(SemaphoreError::InvalidTranscript(Box::new(
+                SemaphoreError::DepthMismatch {
+                    expected: depth,
+                    actual: self.depth,
+                },
+            )));
+        }
+        if self.topic != topic {
+            return Err(SemaphoreError::InvalidTranscript(Box::new(
+                SemaphoreError::InvalidPoll("transcript was recorded for a different topic".into()),
+            )));
+        }
+
+        let mut poll = Poll::from_root(
+            self.root,
+            self.depth,
+            self.topic,
+            self.options.clone(),
+            policy.clone(),
+        )
+        .map_err(|err| SemaphoreError::InvalidTranscript(Box::new(err)))?;
+        for ballot in self.ballots.iter() {
+            poll.accept_vote(ballot.option, ballot.signal.clone())?;
+        }
+        Ok(poll.tally())
+    }
+
+    /// Returns a byte representation of this transcript.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.write_into(&mut result);
+        result
+    }
+
+    /// Returns a transcript read from the provided bytes.
+    ///
+    /// The number of votes and the size of every proof are checked before the votes and the
+    /// proofs are read, so that malformed lengths cannot cause large allocations or long loops.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes do not represent a valid transcript.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SemaphoreError> {
+        let mut source = SliceReader::new(bytes);
+        if source.read_u8_array::<4>()? != TRANSCRIPT_MAGIC {
+            return Err(DeserializationError::InvalidValue(
+                "invalid transcript magic header".to_string(),
+            )
+            .into());
+        }
+        let version = source.read_u8()?;
+        if version != TRANSCRIPT_FORMAT_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported transcript format version {}; expected version {}",
+                version, TRANSCRIPT_FORMAT_VERSION
+            ))
+            .into());
+        }
+
+        let root = Digest::read_from(&mut source)?;
+        let depth = source.read_u8()? as usize;
+        if !(1..=MAX_TREE_DEPTH).contains(&depth) {
+            return Err(DeserializationError::InvalidValue(format!(
+                "access set depth must be between 1 and {}, but was {}",
+                MAX_TREE_DEPTH, depth
+            ))
+            .into());
+        }
+        let topic = Topic::new(Digest::read_from(&mut source)?);
+        let topic = match source.read_u8()? {
+            0 => topic,
+            1 => topic.with_slashing(),
+            value => {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "invalid slashing flag {}",
+                    value
+                ))
+                .into())
+            }
+        };
+        let mut num_read = TRANSCRIPT_HEADER_SIZE;
+
+        let num_options = source.read_u16()? as usize;
+        let mut options = Vec::with_capacity(num_options);
+        for _ in 0..num_options {
+            let len = source.read_u32()? as usize;
+            let option = String::from_utf8(source.read_u8_vec(len)?).map_err(|_| {
+                DeserializationError::InvalidValue("option is not valid UTF-8".to_string())
+            })?;
+            options.push(option);
+            num_read += 4 + len;
+        }
+        validate_options(&options).map_err(DeserializationError::InvalidValue)?;
+
+        // every vote takes at least the option index, the nullifier, the key share (if the topic
+        // uses slashing), and the proof length
+        let num_ballots = source.read_u32()? as usize;
+        num_read += 4;
+        let min_ballot_size = if topic.slashing() {
+            MIN_BALLOT_SIZE + 64
+        } else {
+            MIN_BALLOT_SIZE
+        };
+        if num_ballots > (bytes.len() - num_read) / min_ballot_size {
+            return Err(DeserializationError::InvalidValue(format!(
+                "{} votes cannot fit into the remaining {} bytes",
+                num_ballots,
+                bytes.len() - num_read
+            ))
+            .into());
+        }
+
+        let mut ballots = Vec::with_capacity(num_ballots);
+        for _ in 0..num_ballots {
+            let option = source.read_u16()? as usize;
+            if option >= options.len() {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "option index {} is out of bounds",
+                    option
+                ))
+                .into());
+            }
+            let nullifier = Digest::read_from(&mut source)?;
+            let key_share = if topic.slashing() {
+                Some(read_key_share(&mut source)?)
+            } else {
+                None
+            };
+            let proof_len = source.read_u32()? as usize;
+            if proof_len > MAX_SIGNAL_PROOF_SIZE {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "signal proof size of {} bytes exceeds the maximum of {} bytes",
+                    proof_len, MAX_SIGNAL_PROOF_SIZE
+                ))
+                .into());
+            }
+            let proof = StarkProof::from_bytes(&source.read_u8_vec(proof_len)?)?;
+            ballots.push(Ballot {
+                option,
+                signal: Signal {
+                    root,
+                    depth,
+                    nullifier,
+                    proof,
+                    key_share,
+                },
+            });
+        }
+
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes.into());
+        }
+
+        Ok(Self {
+            root,
+            depth,
+            topic,
+            options,
+            ballots,
+        })
+    }
+}
+
+impl Serializable for Transcript {
+    /// Serializes this transcript as follows:
+    /// - Magic header [TRANSCRIPT_MAGIC] (4 bytes), and format version
+    ///   [TRANSCRIPT_FORMAT_VERSION] (1 byte).
+    /// - Root of the access set (32 bytes), depth of the access set (1 byte), topic (32 bytes),
+    ///   and a flag which is 1 if the topic uses slashing, and 0 otherwise (1 byte).
+    /// - Number of options (2 bytes), followed by every option as its length (4 bytes) and its
+    ///   UTF-8 bytes.
+    /// - Number of votes (4 bytes), followed by every vote as the option index (2 bytes), the
+    ///   nullifier (32 bytes), the key share if the topic uses slashing (64 bytes), the proof
+    ///   length (4 bytes), and the proof bytes.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8_slice(&TRANSCRIPT_MAGIC);
+        target.write_u8(TRANSCRIPT_FORMAT_VERSION);
+        target.write(self.root);
+        target.write_u8(self.depth as u8);
+        target.write(self.topic.digest());
+        target.write_u8(self.topic.slashing() as u8);
+
+        target.write_u16(self.options.len() as u16);
+        for option in self.options.iter() {
+            target.write_u32(option.len() as u32);
+            target.write_u8_slice(option.as_bytes());
+        }
+
+        target.write_u32(self.ballots.len() as u32);
+        for ballot in self.ballots.iter() {
+            let proof_bytes = ballot.signal.proof.to_bytes();
+            target.write_u16(ballot.option as u16);
+            target.write(ballot.signal.nullifier);
+            if let Some(key_share) = ballot.signal.key_share {
+                target.write_u8_slice(Felt::elements_as_bytes(&key_share));
+            }
+            target.write_u32(proof_bytes.len() as u32);
+            target.write_u8_slice(&proof_bytes);
+        }
+    }
+}
+
+// CONSTANTS
+// ================================================================================================
+
+/// Size of a serialized transcript before the first option: the magic header (4 bytes), the
+/// format version (1 byte), the root (32 bytes), the depth (1 byte), the topic (32 bytes), the
+/// slashing flag (1 byte), and the number of options (2 bytes).
+const TRANSCRIPT_HEADER_SIZE: usize = 73;
+
+/// Size of a serialized vote without the key share and the proof: the option index (2 bytes),
+/// the nullifier (32 bytes), and the proof length (4 bytes).
+const MIN_BALLOT_SIZE: usize = 38;
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns an error if the provided list of options is empty, has duplicates, or is too long to
+/// be serialized.
+fn validate_options(options: &[String]) -> Result<(), String> {
+    if options.is_empty() {
+        return Err("a poll must have at least one option".to_string());
+    }
+    if options.len() > u16::MAX as usize {
+        return Err(format!("a poll cannot have more than {} options", u16::MAX));
+    }
+    let mut unique = HashSet::new();
+    for option in options.iter() {
+        if !unique.insert(option) {
+            return Err(format!("option '{}' is not unique", option));
+        }
+    }
+    Ok(())
+}
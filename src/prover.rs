@@ -1,144 +1,119 @@
 use super::{
-    Digest, Felt, FieldElement, PrivKey, ProofOptions, Prover, PublicInputs, Rescue, SemaphoreAir,
-    TraceTable, HASH_CYCLE_LEN, NUM_HASH_ROUNDS, TRACE_WIDTH,
+    get_key_share_x, get_root_step, get_trace_length, init_nullifier_state, Digest, Felt,
+    FieldElement, Identity, ProofOptions, Prover, PublicInputs, Rescue, SemaphoreAir,
+    SemaphoreError, StarkField, Topic, TraceTable, HASH_CAPACITY, HASH_CYCLE_LEN, KEY_SHARE_OFFSET,
+    MESSAGE_INDEX_SENTINEL, NUM_HASH_ROUNDS, TRACE_WIDTH,
 };
 use winterfell::{FieldExtension, HashFunction, Trace};
 
 // SEMAPHORE PROVER
 // ================================================================================================
 
+/// Defines a prover which generates STARK proofs for Semaphore signals.
+#[derive(Clone)]
 pub struct SemaphoreProver {
     options: ProofOptions,
+    slashing: bool,
 }
 
 impl SemaphoreProver {
+    /// Returns a new prover which generates proofs using the provided options.
+    ///
+    /// Custom options can be built using [ProofOptionsBuilder].
+    pub fn new(options: ProofOptions) -> Self {
+        Self {
+            options,
+            slashing: false,
+        }
+    }
+
+    /// Returns this prover configured to reveal key shares in public inputs of generated proofs
+    /// if `slashing` is true; proofs for topics which use slashing must reveal key shares.
+    pub fn with_slashing(mut self, slashing: bool) -> Self {
+        self.slashing = slashing;
+        self
+    }
+
+    /// Returns a new prover which generates proofs using options of the specified preset.
+    pub fn with_preset(preset: SecurityPreset) -> Self {
+        Self::new(preset.options())
+    }
+
     /// Builds an execution trace for the computation required to generate a signal.
+    ///
+    /// The trace contains one hash cycle for hashing the identity secrets, one hash cycle for
+    /// hashing the result into the identity commitment, and one hash cycle for every level of the
+    /// Merkle tree; the trace is padded with additional hash cycles so that its length is a power
+    /// of two. The message index must be smaller than the message limit of the topic;
+    /// otherwise, the trace will not satisfy Semaphore AIR constraints.
+    ///
+    /// # Errors
+    /// Returns an error if the Merkle path is empty.
     pub fn build_trace(
         &self,
-        priv_key: &PrivKey,
+        identity: &Identity,
         key_index: usize,
-        topic: Digest,
+        topic: Topic,
+        message_index: u32,
+        message: Digest,
         merkle_path: &[Digest],
-    ) -> TraceTable<Felt> {
+    ) -> Result<TraceTable<Felt>, SemaphoreError> {
+        if merkle_path.is_empty() {
+            return Err(SemaphoreError::EmptyMerklePath);
+        }
+
         // allocate memory to hold the trace table
-        let trace_length = merkle_path.len() * HASH_CYCLE_LEN;
+        let trace_length = get_trace_length(merkle_path.len() - 1);
         let mut trace = TraceTable::new(TRACE_WIDTH, trace_length);
 
-        // skip the first node in the path since it will be generated by hashing priv_key
+        // skip the first node in the path since it will be generated by hashing the identity
         let merkle_path = &merkle_path[1..];
 
-        let priv_key = priv_key.elements();
-        let topic: [Felt; 4] = topic.into();
+        let nullifier_secret = identity.nullifier_secret();
+        let trapdoor = identity.trapdoor();
+        let message_limit = topic.message_limit();
+        let topic: [Felt; 4] = topic.digest().into();
+        let message: [Felt; 4] = message.into();
 
         trace.fill(
             |state| {
                 // initialize first state of the computation
 
                 // -- merkle path section of the trace --
-                state[0] = Felt::new(8);
-                state[1] = Felt::ZERO;
-                state[2] = Felt::ZERO;
-                state[3] = Felt::ZERO;
-                state[4] = priv_key[0];
-                state[5] = priv_key[1];
-                state[6] = priv_key[2];
-                state[7] = priv_key[3];
-                state[8] = Felt::ZERO;
-                state[9] = Felt::ZERO;
-                state[10] = Felt::ZERO;
-                state[11] = Felt::ZERO;
+                state[..4].copy_from_slice(&HASH_CAPACITY);
+                state[4] = nullifier_secret[0];
+                state[5] = nullifier_secret[1];
+                state[6] = nullifier_secret[2];
+                state[7] = nullifier_secret[3];
+                state[8] = trapdoor[0];
+                state[9] = trapdoor[1];
+                state[10] = trapdoor[2];
+                state[11] = trapdoor[3];
 
                 // -- nullifier section of the trace --
-                // state[12] = Felt::new(8);
-                state[12] = Felt::new(1);
-                state[13] = Felt::ZERO;
-		        state[14] = Felt::ZERO;
-                state[15] = Felt::ZERO;
-                // state[15] = Felt::new(1);
-                state[16] = priv_key[0];
-                state[17] = priv_key[1];
-                state[18] = priv_key[2];
-                state[19] = priv_key[3];
-                state[20] = topic[0];
-                state[21] = topic[1];
-                state[22] = topic[2];
-                state[23] = topic[3];
-
-                // -- index bits column --
+                state[12..24].copy_from_slice(&init_nullifier_state(
+                    nullifier_secret,
+                    topic,
+                    message_index,
+                ));
+
+                // -- index bit and index accumulator columns --
                 state[24] = Felt::ZERO;
+                state[25] = get_index_accumulator(key_index, merkle_path.len(), 0);
+
+                // -- message index range check columns --
+                state[26] = Felt::from(message_index) + MESSAGE_INDEX_SENTINEL;
+                state[27] = Felt::from(message_limit) - Felt::ONE - Felt::from(message_index)
+                    + MESSAGE_INDEX_SENTINEL;
+
+                // -- key share columns --
+                state[KEY_SHARE_OFFSET..KEY_SHARE_OFFSET + 4].copy_from_slice(&nullifier_secret);
+                state[KEY_SHARE_OFFSET + 4..KEY_SHARE_OFFSET + 8].copy_from_slice(&trapdoor);
             },
-            |step, state| {
-                // execute the transition function for all steps
-
-                // determine which cycle we are in and also where in the cycle we are
-                let cycle_num = step / HASH_CYCLE_LEN;
-                let cycle_pos = step % HASH_CYCLE_LEN;
-
-                if cycle_pos < NUM_HASH_ROUNDS {
-                    // compute one round of Rescue hash in columns [0..12] for private key
-                    // hashing and Merkle branch verification
-                    apply_rescue_round(&mut state[..12], cycle_pos);
-                    // compute one round of Rescue hash in columns [12..24] for nullifier
-                    // computation
-                    apply_rescue_round(&mut state[12..24], cycle_pos);
-                } else {
-                    let index_bit = Felt::new(((key_index >> cycle_num) & 1) as u64);
-                    let path_node: [Felt; 4] = merkle_path[cycle_num].into();
-
-                    if index_bit == Felt::ZERO {
-                        // if index bit is zero, next path node goes into columns [8, 9, 10, 11];
-                        // values in columns [4, 5, 6, 7] (the accumulated hash) remain unchanged
-                        state[8] = path_node[0];
-                        state[9] = path_node[1];
-                        state[10] = path_node[2];
-                        state[11] = path_node[3];
-                    } else {
-                        // if index bit is one, accumulated hash goes into columns [8, 9, 10, 11],
-                        // and new path nodes goes into columns [4, 5, 6, 7]
-                        state[8] = state[4];
-                        state[9] = state[5];
-                        state[10] = state[6];
-                        state[11] = state[7];
-                        state[4] = path_node[0];
-                        state[5] = path_node[1];
-                        state[6] = path_node[2];
-                        state[7] = path_node[3];
-                    }
-                    // reset the capacity columns of the state by setting the first element to
-                    // 8 (the number of elements to be hashed) and the rest to ZERO
-                    state[0] = Felt::new(8);
-                    state[1] = Felt::ZERO;
-                    state[2] = Felt::ZERO;
-                    state[3] = Felt::ZERO;
-
-                    // make sure columns [16, 17, 18, 19] are the same as columns [4, 5, 6, 7],
-                    // and everything else is set to ZERO
-                    state[12] = Felt::ZERO;
-                    state[13] = Felt::ZERO;
-                    state[14] = Felt::ZERO;
-                    state[15] = Felt::ZERO;
-                    state[16] = state[4];
-                    state[17] = state[5];
-                    state[18] = state[6];
-                    state[19] = state[7];
-                    state[20] = Felt::ZERO;
-                    state[21] = Felt::ZERO;
-                    state[22] = Felt::ZERO;
-                    state[23] = Felt::ZERO;
-
-                    // save index bit in column 12
-                    state[24] = index_bit;
-                }
-            },
+            |step, state| update_state(step, state, key_index, message, merkle_path),
         );
 
-        // set index bit at the second step to one; this still results in a valid execution trace
-        // because actual index bits are inserted into the trace after step 7, but it ensures
-        // that there are no repeating patterns in the index bit column, and thus the degree
-        // of the index bit constraint is stable.
-        trace.set(24, 1, FieldElement::ONE);
-
-        trace
+        Ok(trace)
     }
 }
 
@@ -165,19 +140,53 @@ impl Prover for SemaphoreProver {
             trace.get(19, 7),
         ];
 
-        // root of the access set Merkle tree should be in the last row, columns [4, 5, 6, 7]
-        let last_row_idx = trace.length() - 1;
+        // hash of the message should be in the 8th row, columns [20, 21, 22, 23]
+        let message = [
+            trace.get(20, HASH_CYCLE_LEN),
+            trace.get(21, HASH_CYCLE_LEN),
+            trace.get(22, HASH_CYCLE_LEN),
+            trace.get(23, HASH_CYCLE_LEN),
+        ];
+
+        // message index range check columns at the first row should sum up to the message
+        // limit minus one, plus two sentinel bits
+        let message_limit = trace.get(26, 0) + trace.get(27, 0) + Felt::ONE
+            - MESSAGE_INDEX_SENTINEL
+            - MESSAGE_INDEX_SENTINEL;
+
+        // key share should be in the 8th row, columns [28..36]
+        let key_share = if self.slashing {
+            let mut key_share = [Felt::ZERO; 8];
+            for (i, value) in key_share.iter_mut().enumerate() {
+                *value = trace.get(KEY_SHARE_OFFSET + i, HASH_CYCLE_LEN);
+            }
+            Some(key_share)
+        } else {
+            None
+        };
+
+        // index accumulator at the first row holds 2^(depth + 1) + 2 * key_index, and thus, the
+        // depth of the access set Merkle tree is one less than the position of its highest bit
+        let tree_depth = (63 - trace.get(25, 0).as_int().leading_zeros()) as usize - 1;
+
+        // root of the access set Merkle tree should be in the row at which the last level of the
+        // tree is hashed, columns [4, 5, 6, 7]
+        let root_step = get_root_step(tree_depth);
         let tree_root = [
-            trace.get(4, last_row_idx),
-            trace.get(5, last_row_idx),
-            trace.get(6, last_row_idx),
-            trace.get(7, last_row_idx),
+            trace.get(4, root_step),
+            trace.get(5, root_step),
+            trace.get(6, root_step),
+            trace.get(7, root_step),
         ];
 
         PublicInputs {
             tree_root: tree_root.into(),
+            tree_depth,
             nullifier: nullifier.into(),
             topic: topic.into(),
+            message: message.into(),
+            message_limit: message_limit.as_int() as u32,
+            key_share,
         }
     }
 
@@ -188,6 +197,10 @@ impl Prover for SemaphoreProver {
 
 impl Default for SemaphoreProver {
     /// Returns a default prover configured for ~95 bit security level.
+    ///
+    /// The default options (32 queries, blowup factor 8, no grinding) predate security presets,
+    /// and are kept as they are so that the default proofs do not change; they are close to, but
+    /// not the same as, the options of [SecurityPreset::Fast96].
     fn default() -> Self {
         let options = ProofOptions::new(
             32,
@@ -198,13 +211,338 @@ impl Default for SemaphoreProver {
             8,
             128,
         );
-        Self { options }
+        Self::new(options)
     }
 }
 
+// SECURITY PRESETS
+// ================================================================================================
+
+/// Defines named sets of proof options which target specific security levels.
+///
+/// The security levels are conjectured security levels as reported by
+/// `StarkProof::security_level()` for all supported access set sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityPreset {
+    /// ~96 bits of security; 27 queries with blowup factor of 8, 16 bits of grinding, BLAKE3
+    /// hash function, and quadratic field extension. This results in smallest proofs.
+    Fast96,
+    /// ~128 bits of security; 29 queries with blowup factor of 16, 16 bits of grinding, BLAKE3
+    /// hash function, and cubic field extension.
+    Standard128,
+    /// ~128 bits of security; 40 queries with blowup factor of 32, 16 bits of grinding, SHA3
+    /// hash function, and cubic field extension.
+    ///
+    /// The security level is limited by collision resistance of the hash function, but the
+    /// security provided by queries is over 200 bits. Thus, this preset leaves a large margin in
+    /// case conjectures about security of FRI protocol turn out to be too optimistic.
+    Paranoid,
+}
+
+impl SecurityPreset {
+    /// Returns proof options defined by this preset.
+    pub fn options(&self) -> ProofOptions {
+        // every preset sets options within the bounds checked by build(); this is exercised for
+        // all presets by the prover preset tests
+        ProofOptionsBuilder::from_preset(*self)
+            .build()
+            .expect("preset options are not valid")
+    }
+}
+
+// PROOF OPTIONS BUILDER
+// ================================================================================================
+
+/// Builds custom [ProofOptions] for [SemaphoreProver].
+///
+/// The builder is initialized with options of one of the presets, and individual options can then
+/// be overridden. For example:
+/// ```
+/// # use semaphore::{ProofOptionsBuilder, SecurityPreset, SemaphoreProver};
+/// let options = ProofOptionsBuilder::from_preset(SecurityPreset::Standard128)
+///     .num_queries(40)
+///     .build()
+///     .unwrap();
+/// let prover = SemaphoreProver::new(options);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProofOptionsBuilder {
+    num_queries: usize,
+    blowup_factor: usize,
+    grinding_factor: u32,
+    hash_fn: HashFunction,
+    field_extension: FieldExtension,
+    fri_folding_factor: usize,
+    fri_max_remainder_size: usize,
+}
+
+impl ProofOptionsBuilder {
+    /// Returns a new builder initialized with options of the specified preset.
+    pub fn from_preset(preset: SecurityPreset) -> Self {
+        let (num_queries, blowup_factor, hash_fn, field_extension) = match preset {
+            SecurityPreset::Fast96 => (27, 8, HashFunction::Blake3_256, FieldExtension::Quadratic),
+            SecurityPreset::Standard128 => {
+                (29, 16, HashFunction::Blake3_256, FieldExtension::Cubic)
+            }
+            SecurityPreset::Paranoid => (40, 32, HashFunction::Sha3_256, FieldExtension::Cubic),
+        };
+        Self {
+            num_queries,
+            blowup_factor,
+            grinding_factor: 16,
+            hash_fn,
+            field_extension,
+            fri_folding_factor: 8,
+            fri_max_remainder_size: 128,
+        }
+    }
+
+    /// Sets the number of queries.
+    pub fn num_queries(mut self, num_queries: usize) -> Self {
+        self.num_queries = num_queries;
+        self
+    }
+
+    /// Sets the blowup factor; this must be at least 8 for Semaphore AIR.
+    pub fn blowup_factor(mut self, blowup_factor: usize) -> Self {
+        self.blowup_factor = blowup_factor;
+        self
+    }
+
+    /// Sets the number of bits of proof-of-work required from the prover.
+    pub fn grinding_factor(mut self, grinding_factor: u32) -> Self {
+        self.grinding_factor = grinding_factor;
+        self
+    }
+
+    /// Sets the hash function used for commitments.
+    pub fn hash_fn(mut self, hash_fn: HashFunction) -> Self {
+        self.hash_fn = hash_fn;
+        self
+    }
+
+    /// Sets the field extension used for the composition polynomial.
+    pub fn field_extension(mut self, field_extension: FieldExtension) -> Self {
+        self.field_extension = field_extension;
+        self
+    }
+
+    /// Sets the FRI folding factor.
+    pub fn fri_folding_factor(mut self, fri_folding_factor: usize) -> Self {
+        self.fri_folding_factor = fri_folding_factor;
+        self
+    }
+
+    /// Sets the maximum size of the FRI remainder.
+    pub fn fri_max_remainder_size(mut self, fri_max_remainder_size: usize) -> Self {
+        self.fri_max_remainder_size = fri_max_remainder_size;
+        self
+    }
+
+    /// Returns [ProofOptions] built from the options of this builder.
+    ///
+    /// # Errors
+    /// Returns an error if any of the options is outside of the range supported by the STARK
+    /// prover (e.g., the number of queries is zero, or the blowup factor is not a power of two),
+    /// or if the blowup factor is smaller than 8.
+    pub fn build(self) -> Result<ProofOptions, SemaphoreError> {
+        check_option(
+            "number of queries",
+            self.num_queries,
+            1,
+            MAX_NUM_QUERIES,
+            false,
+        )?;
+        check_option(
+            "blowup factor",
+            self.blowup_factor,
+            MIN_BLOWUP_FACTOR,
+            MAX_BLOWUP_FACTOR,
+            true,
+        )?;
+        check_option(
+            "grinding factor",
+            self.grinding_factor as usize,
+            0,
+            MAX_GRINDING_FACTOR,
+            false,
+        )?;
+        check_option(
+            "FRI folding factor",
+            self.fri_folding_factor,
+            MIN_FRI_FOLDING_FACTOR,
+            MAX_FRI_FOLDING_FACTOR,
+            true,
+        )?;
+        check_option(
+            "FRI max remainder size",
+            self.fri_max_remainder_size,
+            MIN_FRI_REMAINDER_SIZE,
+            MAX_FRI_REMAINDER_SIZE,
+            true,
+        )?;
+
+        Ok(ProofOptions::new(
+            self.num_queries,
+            self.blowup_factor,
+            self.grinding_factor,
+            self.hash_fn,
+            self.field_extension,
+            self.fri_folding_factor,
+            self.fri_max_remainder_size,
+        ))
+    }
+}
+
+// CONSTANTS
+// ================================================================================================
+
+// Bounds on proof options supported by the STARK prover; the blowup factor must also be at least
+// 8 because of the degree of Semaphore AIR transition constraints.
+const MAX_NUM_QUERIES: usize = 128;
+const MIN_BLOWUP_FACTOR: usize = 8;
+const MAX_BLOWUP_FACTOR: usize = 128;
+const MAX_GRINDING_FACTOR: usize = 32;
+const MIN_FRI_FOLDING_FACTOR: usize = 4;
+const MAX_FRI_FOLDING_FACTOR: usize = 16;
+const MIN_FRI_REMAINDER_SIZE: usize = 32;
+const MAX_FRI_REMAINDER_SIZE: usize = 1024;
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Executes the transition function of the computation for the specified step.
+///
+/// The `merkle_path` is expected to start with the sibling of the leaf, i.e. the leaf itself
+/// (which is generated by hashing the identity) should not be included.
+pub fn update_state(
+    step: usize,
+    state: &mut [Felt],
+    key_index: usize,
+    message: [Felt; 4],
+    merkle_path: &[Digest],
+) {
+    // determine which cycle we are in and also where in the cycle we are
+    let cycle_num = step / HASH_CYCLE_LEN;
+    let cycle_pos = step % HASH_CYCLE_LEN;
+
+    // shift the message index range check columns right by one bit at every step; once the
+    // sentinel bit is shifted out, the columns remain ZERO
+    state[26] = Felt::new(state[26].as_int() >> 1);
+    state[27] = Felt::new(state[27].as_int() >> 1);
+
+    if cycle_pos < NUM_HASH_ROUNDS {
+        // compute one round of Rescue hash in columns [0..12] for identity
+        // hashing and Merkle branch verification
+        apply_rescue_round(&mut state[..12], cycle_pos);
+        // compute one round of Rescue hash in columns [12..24] for nullifier
+        // computation
+        apply_rescue_round(&mut state[12..24], cycle_pos);
+    } else {
+        // the next index bit is the lowest bit of the index accumulator. At the end of the first
+        // cycle, the hash of the identity secrets is hashed with zero into the identity
+        // commitment; thus, the first index bit is ZERO, and the first path node is ZEROs. After
+        // the root is computed, padding cycles hash the root with ZEROs as well
+        let index_bit = get_index_accumulator(key_index, merkle_path.len(), cycle_num).as_int() & 1;
+        let index_bit = Felt::new(index_bit);
+        let path_node: [Felt; 4] = match cycle_num {
+            0 => [Felt::ZERO; 4],
+            _ => merkle_path
+                .get(cycle_num - 1)
+                .map_or([Felt::ZERO; 4], |&node| node.into()),
+        };
+
+        if index_bit == Felt::ZERO {
+            // if index bit is zero, next path node goes into columns [8, 9, 10, 11];
+            // values in columns [4, 5, 6, 7] (the accumulated hash) remain unchanged
+            state[8] = path_node[0];
+            state[9] = path_node[1];
+            state[10] = path_node[2];
+            state[11] = path_node[3];
+        } else {
+            // if index bit is one, accumulated hash goes into columns [8, 9, 10, 11],
+            // and new path nodes goes into columns [4, 5, 6, 7]
+            state[8] = state[4];
+            state[9] = state[5];
+            state[10] = state[6];
+            state[11] = state[7];
+            state[4] = path_node[0];
+            state[5] = path_node[1];
+            state[6] = path_node[2];
+            state[7] = path_node[3];
+        }
+        // reset the capacity columns of the state by setting the first element to
+        // 8 (the number of elements to be hashed) and the rest to ZERO
+        state[..4].copy_from_slice(&HASH_CAPACITY);
+
+        // remember the slope of the key share before the nullifier section is overwritten
+        let slope = [
+            state[20], state[21], state[22], state[23], state[12], state[13], state[14], state[15],
+        ];
+
+        // make sure columns [16, 17, 18, 19] are the same as columns [4, 5, 6, 7];
+        // at the end of the first cycle, the message hash goes into columns [20, 21, 22, 23],
+        // and everything else is set to ZERO
+        let message = if cycle_num == 0 {
+            message
+        } else {
+            [Felt::ZERO; 4]
+        };
+        state[12] = Felt::ZERO;
+        state[13] = Felt::ZERO;
+        state[14] = Felt::ZERO;
+        state[15] = Felt::ZERO;
+        state[16] = state[4];
+        state[17] = state[5];
+        state[18] = state[6];
+        state[19] = state[7];
+        state[20] = message[0];
+        state[21] = message[1];
+        state[22] = message[2];
+        state[23] = message[3];
+
+        // add slope * x to the key share columns, where x is the coordinate bound to the message
+        // hash; this yields the key share at the end of the first cycle
+        let x = get_key_share_x(&message);
+        for (i, &value) in slope.iter().enumerate() {
+            state[KEY_SHARE_OFFSET + i] += value * x;
+        }
+
+        // save index bit in column 24, and remove it from the index accumulator in column 25
+        state[24] = index_bit;
+        state[25] = get_index_accumulator(key_index, merkle_path.len(), cycle_num + 1);
+    }
+}
+
+/// Returns the value of the index accumulator column during the specified hash cycle.
+///
+/// The accumulator holds 2^(depth + 1) + 2 * key_index during the first cycle (the lowest bit is
+/// the bit of the identity commitment cycle), and is shifted right by one bit at the start of
+/// every subsequent cycle; thus, it reaches ONE during the cycle which computes the root, and
+/// ZERO during padding cycles.
+fn get_index_accumulator(key_index: usize, depth: usize, cycle_num: usize) -> Felt {
+    Felt::new((((1 << (depth + 1)) + (key_index << 1)) >> cycle_num) as u64)
+}
+
+/// Checks that a proof option is in the specified range, and, if `power_of_two` is true, that it
+/// is a power of two.
+fn check_option(
+    name: &str,
+    value: usize,
+    min: usize,
+    max: usize,
+    power_of_two: bool,
+) -> Result<(), SemaphoreError> {
+    if value < min || value > max || (power_of_two && !value.is_power_of_two()) {
+        let kind = if power_of_two { "a power of two " } else { "" };
+        return Err(SemaphoreError::InvalidProofOptions(format!(
+            "{} must be {}between {} and {}, but was {}",
+            name, kind, min, max, value
+        )));
+    }
+    Ok(())
+}
+
 fn apply_rescue_round(state: &mut [Felt], round: usize) {
     let mut state_array = [Felt::ZERO; 12];
     for (a_val, &s_val) in state_array.iter_mut().zip(state.iter()) {
@@ -0,0 +1,238 @@
+use super::{
+    incremental::RootHistory, AccessSet, Digest, IncrementalAccessSet, NullifierStore,
+    SemaphoreError, Signal, SignalVerifier, Topic, VerificationPolicy, MAX_TREE_DEPTH,
+};
+use std::collections::BTreeMap;
+
+// GROUP SIGNAL
+// ================================================================================================
+
+/// Identifier of a group in a [GroupRegistry].
+pub type GroupId = u64;
+
+/// Defines a signal made by a member of a group in a [GroupRegistry].
+///
+/// The group ID is not bound to the proof of the signal; it only tells the registry which access
+/// set the signal was made against, and a signal submitted with a wrong group ID fails
+/// verification.
+#[derive(Debug, Clone)]
+pub struct GroupSignal {
+    pub group_id: GroupId,
+    pub signal: Signal,
+}
+
+impl GroupSignal {
+    /// Returns a signal made by a member of the group with the specified ID.
+    pub fn new(group_id: GroupId, signal: Signal) -> Self {
+        Self { group_id, signal }
+    }
+}
+
+// GROUP
+// ================================================================================================
+
+/// Describes the access set of a group in a [GroupRegistry].
+///
+/// A group does not hold public keys of its members; it holds only the recent roots of the access
+/// set of the group, the depth of the access set, and the policy which signals must satisfy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    roots: RootHistory,
+    depth: usize,
+    policy: VerificationPolicy,
+}
+
+impl Group {
+    /// Returns a new group with an access set of the specified root and depth, which keeps up to
+    /// `history_size` most recent roots.
+    ///
+    /// # Errors
+    /// Returns an error if the depth is not between 1 and 32, or if the history size is 0.
+    pub fn new(root: Digest, depth: usize, history_size: usize) -> Result<Self, SemaphoreError> {
+        if !(1..=MAX_TREE_DEPTH).contains(&depth) {
+            return Err(SemaphoreError::InvalidDepth(depth));
+        }
+        Ok(Self {
+            roots: RootHistory::new(root, history_size)?,
+            depth,
+            policy: VerificationPolicy::default(),
+        })
+    }
+
+    /// Returns this group with its verification policy replaced by the provided one.
+    pub fn with_policy(mut self, policy: VerificationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the current root of the access set of this group.
+    pub fn root(&self) -> Digest {
+        self.roots.current()
+    }
+
+    /// Returns the recent roots of the access set of this group ordered from the oldest to the
+    /// current one.
+    pub fn root_history(&self) -> impl Iterator<Item = &Digest> {
+        self.roots.iter()
+    }
+
+    /// Returns the depth of the access set of this group.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the policy which signals must satisfy to be accepted for this group.
+    pub fn policy(&self) -> &VerificationPolicy {
+        &self.policy
+    }
+
+    /// Returns a verifier of signals against the root history, the depth, and the policy of this
+    /// group.
+    fn verifier(&self) -> SignalVerifier {
+        SignalVerifier::from_root_history(&self.roots, self.depth, self.policy.clone())
+    }
+}
+
+impl From<&AccessSet> for Group {
+    /// Returns a group with the root, the depth, and the policy of the provided access set; the
+    /// group keeps only the current root.
+    fn from(access_set: &AccessSet) -> Self {
+        // the depth of an access set is always valid, and a history of size 1 is never empty
+        Self::new(access_set.root(), access_set.depth(), 1)
+            .expect("history of a single root is valid")
+            .with_policy(access_set.policy().clone())
+    }
+}
+
+impl From<&IncrementalAccessSet> for Group {
+    /// Returns a group with the root history, the depth, and the policy of the provided access
+    /// set.
+    fn from(access_set: &IncrementalAccessSet) -> Self {
+        Self {
+            roots: access_set.roots().clone(),
+            depth: access_set.depth(),
+            policy: access_set.policy().clone(),
+        }
+    }
+}
+
+// GROUP REGISTRY
+// ================================================================================================
+
+/// Maps group IDs to access sets of independent groups.
+///
+/// A registry can verify signals of any registered group without holding the access sets of the
+/// groups; a [GroupSignal] carries the ID of the group, and the signal is verified against the
+/// recent roots of this group.
+///
+/// Nullifiers do not depend on the group, and thus, a user who is a member of several groups
+/// produces the same nullifier for a topic in all of them. If signals of several groups are
+/// accepted into the same [NullifierStore], topics should include the group ID (e.g., in the
+/// scope of an [ExternalNullifier](super::ExternalNullifier)).
+#[derive(Debug, Default)]
+pub struct GroupRegistry {
+    groups: BTreeMap<GroupId, Group>,
+}
+
+impl GroupRegistry {
+    /// Returns a new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the provided group under the specified ID, and returns the group previously
+    /// registered under this ID, if any.
+    pub fn insert(&mut self, group_id: GroupId, group: Group) -> Option<Group> {
+        self.groups.insert(group_id, group)
+    }
+
+    /// Removes the group with the specified ID from this registry, and returns it.
+    pub fn remove(&mut self, group_id: GroupId) -> Option<Group> {
+        self.groups.remove(&group_id)
+    }
+
+    /// Returns the group with the specified ID.
+    pub fn get(&self, group_id: GroupId) -> Option<&Group> {
+        self.groups.get(&group_id)
+    }
+
+    /// Returns the number of groups in this registry.
+    pub fn num_groups(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Makes the provided root the current root of the group with the specified ID; the previous
+    /// root is retained in the root history of the group.
+    ///
+    /// # Errors
+    /// Returns an error if no group with the specified ID is registered.
+    pub fn update_root(&mut self, group_id: GroupId, root: Digest) -> Result<(), SemaphoreError> {
+        let group = self
+            .groups
+            .get_mut(&group_id)
+            .ok_or(SemaphoreError::UnknownGroup(group_id))?;
+        group.roots.push(root);
+        Ok(())
+    }
+
+    /// Makes the provided root the current root of the group with the specified ID, and removes
+    /// all previous roots from the root history of the group.
+    ///
+    /// This should be used instead of [GroupRegistry::update_root()] when a key is removed from
+    /// or replaced in the access set of the group; otherwise, the old key could still signal
+    /// against the previous roots.
+    ///
+    /// # Errors
+    /// Returns an error if no group with the specified ID is registered.
+    pub fn revoke_root_history(
+        &mut self,
+        group_id: GroupId,
+        root: Digest,
+    ) -> Result<(), SemaphoreError> {
+        let group = self
+            .groups
+            .get_mut(&group_id)
+            .ok_or(SemaphoreError::UnknownGroup(group_id))?;
+        group.roots.push(root);
+        group.roots.revoke_previous();
+        Ok(())
+    }
+
+    /// Returns Ok(()) if the provided signal is a valid signal with the specified message on the
+    /// specified topic made against any root in the root history of the group of the signal.
+    pub fn verify_signal(
+        &self,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        signal: GroupSignal,
+    ) -> Result<(), SemaphoreError> {
+        let group = self
+            .get(signal.group_id)
+            .ok_or(SemaphoreError::UnknownGroup(signal.group_id))?;
+        group
+            .verifier()
+            .verify_signal(topic, message, signal.signal)
+    }
+
+    /// Verifies the provided signal and records its nullifier in the provided store.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The signal is not valid (as defined by [GroupRegistry::verify_signal()]).
+    /// - A signal with the same nullifier has already been recorded in the store for this topic.
+    /// - The store could not be read or updated.
+    pub fn accept_signal<S: NullifierStore>(
+        &self,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        signal: GroupSignal,
+        store: &mut S,
+    ) -> Result<(), SemaphoreError> {
+        let group = self
+            .get(signal.group_id)
+            .ok_or(SemaphoreError::UnknownGroup(signal.group_id))?;
+        group
+            .verifier()
+            .accept_signal(topic, message, signal.signal, store)
+    }
+}
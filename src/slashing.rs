@@ -0,0 +1,243 @@
+use super::{
+    get_key_share_x, hash_bytes, read_key_share, Digest, Felt, FieldElement, Identity, KeyShare,
+    SemaphoreError, Signal, Topic,
+};
+use winter_utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
+// SLASHING EVIDENCE
+// ================================================================================================
+
+/// Defines evidence that a user made two signals with the same nullifier but different messages
+/// on a topic which uses slashing.
+///
+/// Every signal on such a topic reveals a key share, which is a point (x, y) on a degree-1
+/// polynomial y = secret + slope * x computed independently for each of the 8 elements of the
+/// identity (the nullifier secret followed by the trapdoor). The x coordinate is a combination of
+/// all elements of the message hash, and the slope is derived from the nullifier secret, the
+/// topic, and the message index; thus, a single signal reveals one point on every polynomial,
+/// while two signals with the same nullifier but different messages reveal two points on the same
+/// polynomial, and the identity can be recovered from them.
+///
+/// The x coordinate is a single field element, and thus, a user can find two messages with the
+/// same coordinate with a birthday search over roughly 2^32 message hashes; signals with such
+/// messages reveal the same point, and are rejected as evidence. This is an accepted limitation
+/// (see [recover_identity_from_double_signal()]).
+///
+/// The evidence is self-contained: it can be exported, and anyone can recover the identity from
+/// it and check that the identity corresponds to the nullifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlashingEvidence {
+    pub topic: Topic,
+    pub nullifier: Digest,
+    /// Two key shares, each as a pair of the message hash (from which the x coordinate is
+    /// derived) and the key share itself.
+    pub key_shares: [(Digest, KeyShare); 2],
+}
+
+impl SlashingEvidence {
+    /// Returns evidence built from two signals with the specified messages on the specified topic.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The topic does not use slashing, or any of the signals does not contain a key share.
+    /// - The signals have different nullifiers.
+    /// - The messages yield the same x coordinate (e.g., the messages are the same); in this case
+    ///   the key shares are the same, and the identity cannot be recovered.
+    pub fn new(
+        topic: impl Into<Topic>,
+        first_message: &[u8],
+        first_signal: &Signal,
+        second_message: &[u8],
+        second_signal: &Signal,
+    ) -> Result<Self, SemaphoreError> {
+        let topic = topic.into();
+        if !topic.slashing() {
+            return Err(invalid_evidence("topic does not use slashing"));
+        }
+        if first_signal.nullifier != second_signal.nullifier {
+            return Err(invalid_evidence("signals have different nullifiers"));
+        }
+
+        let mut key_shares = [(Digest::default(), [Felt::ZERO; 8]); 2];
+        let signals = [
+            (first_message, first_signal),
+            (second_message, second_signal),
+        ];
+        for (key_share, (message, signal)) in key_shares.iter_mut().zip(signals.iter()) {
+            let y = signal.key_share.ok_or(SemaphoreError::MissingKeyShare)?;
+            *key_share = (hash_bytes(message), y);
+        }
+
+        let evidence = Self {
+            topic,
+            nullifier: first_signal.nullifier,
+            key_shares,
+        };
+        evidence.get_x_coordinates()?;
+        Ok(evidence)
+    }
+
+    /// Returns the identity recovered from the key shares of this evidence.
+    ///
+    /// # Errors
+    /// Returns an error if the key shares have the same x coordinate, or if the recovered
+    /// identity does not yield the nullifier of this evidence for any message index allowed by the
+    /// topic (i.e., the key shares were not revealed by valid signals). The nullifier does not
+    /// depend on the trapdoor, and thus, the trapdoor is not checked; to make sure the key shares
+    /// were not tampered with, check that the public key of the recovered identity is in the
+    /// access set.
+    pub fn recover_identity(&self) -> Result<Identity, SemaphoreError> {
+        let (x1, x2) = self.get_x_coordinates()?;
+
+        // interpolate the polynomial through the two points and evaluate it at x = 0
+        let [(_, y1), (_, y2)] = self.key_shares;
+        let mut elements = [Felt::ZERO; 8];
+        for (i, element) in elements.iter_mut().enumerate() {
+            let slope = (y1[i] - y2[i]) / (x1 - x2);
+            *element = y1[i] - slope * x1;
+        }
+        let mut nullifier_secret = [Felt::ZERO; 4];
+        let mut trapdoor = [Felt::ZERO; 4];
+        nullifier_secret.copy_from_slice(&elements[..4]);
+        trapdoor.copy_from_slice(&elements[4..]);
+        let identity = Identity::new(nullifier_secret, trapdoor);
+
+        let topic = self.topic.digest();
+        if !(0..self.topic.message_limit())
+            .any(|message_index| identity.get_nullifier_at(topic, message_index) == self.nullifier)
+        {
+            return Err(invalid_evidence(
+                "recovered identity does not match the nullifier",
+            ));
+        }
+
+        Ok(identity)
+    }
+
+    /// Returns a byte representation of this evidence.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.write_into(&mut result);
+        result
+    }
+
+    /// Returns evidence read from the provided bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes do not represent valid evidence.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SemaphoreError> {
+        let mut source = SliceReader::new(bytes);
+        let evidence = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes.into());
+        }
+        Ok(evidence)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the x coordinates of the key shares of this evidence.
+    ///
+    /// # Errors
+    /// Returns an error if the key shares have the same x coordinate.
+    fn get_x_coordinates(&self) -> Result<(Felt, Felt), SemaphoreError> {
+        let [(first_message, _), (second_message, _)] = self.key_shares;
+        let first_message: [Felt; 4] = first_message.into();
+        let second_message: [Felt; 4] = second_message.into();
+        let x1 = get_key_share_x(&first_message);
+        let x2 = get_key_share_x(&second_message);
+        if x1 == x2 {
+            return Err(invalid_evidence("key shares have the same x coordinate"));
+        }
+        Ok((x1, x2))
+    }
+}
+
+impl Serializable for SlashingEvidence {
+    /// Serializes this evidence as follows:
+    /// - Topic (32 bytes), followed by the message limit of the topic (4 bytes).
+    /// - Nullifier (32 bytes).
+    /// - Two key shares, each as the message hash (32 bytes) followed by the key share (64 bytes).
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write(self.topic.digest());
+        target.write_u32(self.topic.message_limit());
+        target.write(self.nullifier);
+        for (message, key_share) in self.key_shares.iter() {
+            target.write(*message);
+            target.write_u8_slice(Felt::elements_as_bytes(key_share));
+        }
+    }
+}
+
+impl Deserializable for SlashingEvidence {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let topic = Digest::read_from(source)?;
+        let message_limit = source.read_u32()?;
+        let topic = Topic::new(topic)
+            .with_message_limit(message_limit)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?
+            .with_slashing();
+
+        let nullifier = Digest::read_from(source)?;
+        let mut key_shares = [(Digest::default(), [Felt::ZERO; 8]); 2];
+        for (message, key_share) in key_shares.iter_mut() {
+            *message = Digest::read_from(source)?;
+            *key_share = read_key_share(source)?;
+        }
+
+        Ok(Self {
+            topic,
+            nullifier,
+            key_shares,
+        })
+    }
+}
+
+// PUBLIC FUNCTIONS
+// ================================================================================================
+
+/// Returns the identity of a user who made the two provided signals with the specified messages
+/// on the specified topic.
+///
+/// The signals are expected to have the same nullifier; such signals are rejected by a
+/// [NullifierStore](crate::NullifierStore) as duplicates, but on a topic which uses slashing,
+/// they also reveal the identity of the user. The signals do not need to be verified first; the
+/// recovered identity is checked against the nullifier.
+///
+/// Signals whose message hashes yield the same x coordinate reveal the same key share, and the
+/// identity cannot be recovered from them; such signals are rejected. As the x coordinate is a
+/// single field element, a user can find two such messages with a birthday search over roughly
+/// 2^32 message hashes, and signal twice without revealing their identity. This is an accepted
+/// limitation of slashing: it deters double signalling, but does not prevent it, and thus,
+/// duplicate signals must still be rejected by a nullifier store.
+///
+/// # Errors
+/// Returns an error if the identity cannot be recovered from the signals (see
+/// [SlashingEvidence::new()] and [SlashingEvidence::recover_identity()]), including when the
+/// messages yield the same x coordinate.
+pub fn recover_identity_from_double_signal(
+    topic: impl Into<Topic>,
+    first_message: &[u8],
+    first_signal: &Signal,
+    second_message: &[u8],
+    second_signal: &Signal,
+) -> Result<Identity, SemaphoreError> {
+    SlashingEvidence::new(
+        topic,
+        first_message,
+        first_signal,
+        second_message,
+        second_signal,
+    )?
+    .recover_identity()
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn invalid_evidence(reason: &str) -> SemaphoreError {
+    SemaphoreError::InvalidEvidence(reason.to_string())
+}
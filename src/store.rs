@@ -0,0 +1,162 @@
+use super::{Digest, HasherDigest, SemaphoreError};
+use std::{
+    collections::HashSet,
+    convert::TryInto,
+    fs::{File, OpenOptions},
+    io::{self, Read, Write},
+    path::Path,
+};
+
+// NULLIFIER STORE
+// ================================================================================================
+
+/// Defines a store of (topic, nullifier) pairs of signals which have already been accepted.
+///
+/// A store is used by [AccessSet::accept_signal()](crate::AccessSet::accept_signal) to reject
+/// signals which were already made by the same user on the same topic.
+pub trait NullifierStore {
+    /// Returns true if the specified (topic, nullifier) pair has been recorded in this store.
+    fn contains(&self, topic: Digest, nullifier: Digest) -> io::Result<bool>;
+
+    /// Records the specified (topic, nullifier) pair in this store.
+    ///
+    /// Returns false if the pair has already been recorded, in which case the store is not
+    /// modified. Checking for presence of the pair and recording it must be done atomically with
+    /// respect to other calls to this method.
+    fn insert(&mut self, topic: Digest, nullifier: Digest) -> io::Result<bool>;
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Key under which a (topic, nullifier) pair is recorded.
+type StoreKey = [u8; RECORD_SIZE];
+
+/// Size of a single record in a file-backed store: 32 bytes of topic followed by 32 bytes of
+/// nullifier.
+const RECORD_SIZE: usize = 64;
+
+fn build_key(topic: Digest, nullifier: Digest) -> StoreKey {
+    let mut key = [0u8; RECORD_SIZE];
+    key[..32].copy_from_slice(&topic.as_bytes());
+    key[32..].copy_from_slice(&nullifier.as_bytes());
+    key
+}
+
+// IN-MEMORY STORE
+// ================================================================================================
+
+/// A [NullifierStore] which keeps all records in memory.
+#[derive(Debug, Default)]
+pub struct InMemoryNullifierStore {
+    records: HashSet<StoreKey>,
+}
+
+impl InMemoryNullifierStore {
+    /// Returns a new empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of records in this store.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if this store contains no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+impl NullifierStore for InMemoryNullifierStore {
+    fn contains(&self, topic: Digest, nullifier: Digest) -> io::Result<bool> {
+        Ok(self.records.contains(&build_key(topic, nullifier)))
+    }
+
+    fn insert(&mut self, topic: Digest, nullifier: Digest) -> io::Result<bool> {
+        Ok(self.records.insert(build_key(topic, nullifier)))
+    }
+}
+
+// FILE-BACKED STORE
+// ================================================================================================
+
+/// A [NullifierStore] which persists records in an append-only file.
+///
+/// Every record is written as 64 bytes: the topic followed by the nullifier. Records are never
+/// modified or removed; all records are also kept in memory so that lookups do not touch the file.
+///
+/// The store assumes that it is the only writer to the file.
+#[derive(Debug)]
+pub struct FileNullifierStore {
+    file: File,
+    records: HashSet<StoreKey>,
+}
+
+impl FileNullifierStore {
+    /// Opens a store backed by the file at the specified path, creating the file if it does not
+    /// exist.
+    ///
+    /// If the last write to the file was interrupted, the file ends with a partial record; the
+    /// record was never added to the store, and thus, the file is truncated to the last complete
+    /// record.
+    ///
+    /// # Errors
+    /// Returns an error if the file could not be opened, read, or truncated.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SemaphoreError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(SemaphoreError::Store)?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)
+            .map_err(SemaphoreError::Store)?;
+        // drop a partial record left by an interrupted write
+        let num_bytes = bytes.len() - bytes.len() % RECORD_SIZE;
+        if num_bytes != bytes.len() {
+            file.set_len(num_bytes as u64)
+                .map_err(SemaphoreError::Store)?;
+            file.sync_data().map_err(SemaphoreError::Store)?;
+        }
+
+        // the remaining bytes hold only complete records, so every chunk is a full record
+        let records = bytes[..num_bytes]
+            .chunks(RECORD_SIZE)
+            .map(|chunk| chunk.try_into().expect("invalid record size"))
+            .collect();
+        Ok(Self { file, records })
+    }
+
+    /// Returns the number of records in this store.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if this store contains no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+impl NullifierStore for FileNullifierStore {
+    fn contains(&self, topic: Digest, nullifier: Digest) -> io::Result<bool> {
+        Ok(self.records.contains(&build_key(topic, nullifier)))
+    }
+
+    fn insert(&mut self, topic: Digest, nullifier: Digest) -> io::Result<bool> {
+        let key = build_key(topic, nullifier);
+        if self.records.contains(&key) {
+            return Ok(false);
+        }
+
+        // the record is added to memory only after it has been persisted
+        self.file.write_all(&key)?;
+        self.file.sync_data()?;
+        self.records.insert(key);
+        Ok(true)
+    }
+}
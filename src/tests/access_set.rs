@@ -0,0 +1,193 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    get_empty_leaf, get_removed_leaf, AccessSet, AccessSetChange, Felt, Identity, MerkleTree,
+    PubKey, Rescue, SemaphoreError, SemaphoreProver,
+};
+
+// SET SIZES
+// ================================================================================================
+
+#[test]
+fn signals_can_be_made_for_access_sets_of_any_size() {
+    let prover = SemaphoreProver::default();
+    for &num_keys in [1, 3, 5, 6, 17].iter() {
+        let (identities, access_set) = build_access_set(num_keys);
+        assert_eq!(num_keys, access_set.num_keys());
+        for &key_idx in [0, num_keys - 1].iter() {
+            let signal = access_set
+                .make_signal(&identities[key_idx], TOPIC, MESSAGE, &prover)
+                .unwrap();
+            assert_eq!(access_set.trace_length(), signal.proof.trace_length());
+            assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_ok());
+        }
+    }
+}
+
+#[test]
+fn access_set_is_padded_with_empty_leaves() {
+    let (identities, access_set) = build_access_set(5);
+    assert_eq!(3, access_set.depth());
+
+    // the root is the same as the root of a tree with 3 empty leaves after the public keys
+    let mut leaves = identities.iter().map(|i| i.pub_key().0).collect::<Vec<_>>();
+    leaves.resize(8, get_empty_leaf());
+    let key_tree = MerkleTree::<Rescue>::new(leaves).unwrap();
+    assert_eq!(*key_tree.root(), access_set.root());
+
+    // a single public key is padded with a single empty leaf
+    let (_, access_set) = build_access_set(1);
+    assert_eq!(1, access_set.depth());
+}
+
+#[test]
+fn access_set_cannot_be_empty() {
+    assert_eq!(
+        SemaphoreError::InvalidSetSize(0),
+        AccessSet::new(Vec::new()).err().unwrap()
+    );
+}
+
+#[test]
+fn key_path_to_empty_leaf_cannot_be_built() {
+    let (_, access_set) = build_access_set(5);
+    assert_eq!(
+        SemaphoreError::InvalidIndex(5),
+        access_set.get_key_path(5).unwrap_err()
+    );
+}
+
+#[test]
+fn empty_leaf_cannot_be_added_as_public_key() {
+    let (identities, _) = build_access_set(3);
+    let mut pub_keys = identities.iter().map(PubKey::new).collect::<Vec<_>>();
+    pub_keys.push(PubKey(get_empty_leaf()));
+    assert_eq!(
+        SemaphoreError::InvalidPubKey,
+        AccessSet::new(pub_keys).err().unwrap()
+    );
+}
+
+#[test]
+fn signal_cannot_be_made_for_identity_outside_of_access_set() {
+    let (identities, access_set) = build_access_set(3);
+    let (other_identities, _) = build_access_set(4);
+    assert!(!identities.contains(&other_identities[3]));
+    let err = access_set
+        .make_signal(
+            &other_identities[3],
+            TOPIC,
+            MESSAGE,
+            &SemaphoreProver::default(),
+        )
+        .unwrap_err();
+    assert_eq!(SemaphoreError::KeyNotInSet, err);
+}
+
+// TREE DEPTH
+// ================================================================================================
+
+#[test]
+fn signal_is_bound_to_tree_depth() {
+    // access sets with 5 and 9 keys have depths 3 and 4, but traces of the same length
+    let (identities, access_set) = build_access_set(9);
+    let (_, other_set) = build_access_set(5);
+    assert_eq!(access_set.trace_length(), other_set.trace_length());
+
+    let signal = access_set
+        .make_signal(&identities[2], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+    assert_eq!(
+        SemaphoreError::SignalDepthMismatch {
+            expected: 3,
+            actual: 4
+        },
+        other_set.verify_signal(TOPIC, MESSAGE, signal).unwrap_err()
+    );
+}
+
+// KEY REMOVAL AND REPLACEMENT
+// ================================================================================================
+
+#[test]
+fn removed_key_cannot_signal() {
+    let prover = SemaphoreProver::default();
+    let (identities, mut access_set) = build_access_set(4);
+    let signal = access_set
+        .make_signal(&identities[1], TOPIC, MESSAGE, &prover)
+        .unwrap();
+
+    let old_root = access_set.root();
+    let record = access_set.remove(1).unwrap();
+    assert_eq!(1, record.key_idx);
+    assert_eq!(
+        AccessSetChange::Removed {
+            pub_key: identities[1].pub_key().0
+        },
+        record.change
+    );
+    assert_eq!(old_root, record.old_root);
+    assert_eq!(access_set.root(), record.new_root);
+    assert_ne!(old_root, access_set.root());
+
+    // the slot of the key holds the removed leaf, and the indexes of other keys do not change
+    assert_eq!(4, access_set.num_keys());
+    assert_eq!(get_removed_leaf(), access_set.get_key_path(1).unwrap()[0]);
+    assert_eq!(
+        identities[2].pub_key().0,
+        access_set.get_key_path(2).unwrap()[0]
+    );
+
+    // a signal made before the removal is no longer valid, but other keys can still signal
+    assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_err());
+    let signal = access_set
+        .make_signal(&identities[2], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_ok());
+}
+
+#[test]
+fn replaced_key_is_rotated() {
+    let prover = SemaphoreProver::default();
+    let (identities, mut access_set) = build_access_set(4);
+    let new_identity = Identity::new([Felt::new(101); 4], [Felt::new(102); 4]);
+
+    let record = access_set.replace(3, new_identity.pub_key()).unwrap();
+    assert_eq!(
+        AccessSetChange::Replaced {
+            old_pub_key: identities[3].pub_key().0,
+            new_pub_key: new_identity.pub_key().0
+        },
+        record.change
+    );
+
+    // the access set is the same as an access set built with the new key
+    let mut pub_keys = identities[..3].iter().map(PubKey::new).collect::<Vec<_>>();
+    pub_keys.push(new_identity.pub_key());
+    assert_eq!(AccessSet::new(pub_keys).unwrap().root(), access_set.root());
+
+    let signal = access_set
+        .make_signal(&new_identity, TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_ok());
+}
+
+#[test]
+fn removed_key_cannot_be_removed_again() {
+    let (_, mut access_set) = build_access_set(3);
+    access_set.remove(2).unwrap();
+    assert_eq!(
+        SemaphoreError::KeyRemoved(2),
+        access_set.remove(2).unwrap_err()
+    );
+}
+
+#[test]
+fn removed_leaf_cannot_be_added_as_public_key() {
+    let (_, mut access_set) = build_access_set(3);
+    assert_eq!(
+        SemaphoreError::InvalidPubKey,
+        access_set
+            .replace(0, PubKey(get_removed_leaf()))
+            .unwrap_err()
+    );
+}
@@ -0,0 +1,394 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    debug_trace, get_key_share_x, get_root_step, prover::update_state, AccessSet, ConstraintKind,
+    Digest, Felt, FieldElement, Hasher, Identity, Prover, PublicInputs, Rescue, SemaphoreAir,
+    SemaphoreError, SemaphoreProver, Topic, Trace, TraceTable, HASH_CYCLE_LEN, NUM_HASH_ROUNDS,
+};
+use winterfell::{Air, AirContext, Assertion, EvaluationFrame, ProofOptions, TraceInfo};
+
+// NULLIFIER CAPACITY
+// ================================================================================================
+
+#[test]
+fn nullifier_with_evil_capacity_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let key_idx = 3;
+    let topic = Topic::from(TOPIC);
+    let key_path = access_set.get_key_path(key_idx).unwrap();
+
+    let prover = SemaphoreProver::default();
+    let trace = build_evil_trace(&prover, &identities[key_idx], key_idx, &key_path);
+
+    // the evil trace yields a second nullifier for the same key and topic
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    assert_eq!(access_set.root(), pub_inputs.tree_root);
+    assert_ne!(
+        identities[key_idx].get_nullifier(topic.digest()),
+        pub_inputs.nullifier
+    );
+
+    // the evil trace violates only the nullifier capacity assertions
+    let air = build_air(&prover, &trace);
+    let names = failure_names(&air, &trace);
+    assert!(!names.is_empty());
+    assert!(names
+        .iter()
+        .all(|name| name.starts_with("nullifier capacity")));
+
+    // a proof of the evil trace is rejected by the verifier
+    assert_evil_proof_is_rejected(&prover, trace);
+}
+
+// HASH INPUTS
+// ================================================================================================
+
+#[test]
+fn public_hash_inputs_are_pinned_at_first_step() {
+    let (identities, access_set) = build_access_set(8);
+    let key_idx = 3;
+    let topic = Topic::from(TOPIC);
+    let message = Rescue::hash(MESSAGE);
+    let key_path = access_set.get_key_path(key_idx).unwrap();
+    let prover = SemaphoreProver::default();
+    let honest = prover
+        .build_trace(&identities[key_idx], key_idx, topic, 0, message, &key_path)
+        .unwrap();
+    let air = build_air(&prover, &honest);
+
+    // Merkle hash capacity, nullifier hash capacity, topic, and the first index bit are not
+    // derived from any secret, and thus, each of them must be fixed by an assertion
+    let columns = [0, 1, 2, 3, 12, 14, 15, 20, 21, 22, 23, 24];
+    for &column in columns.iter() {
+        let mut trace = prover
+            .build_trace(&identities[key_idx], key_idx, topic, 0, message, &key_path)
+            .unwrap();
+        trace.set(column, 0, trace.get(column, 0) + Felt::new(99));
+        let failures = debug_trace(&air, &trace);
+        assert!(
+            failures
+                .iter()
+                .any(|f| f.kind == ConstraintKind::Assertion && f.step == 0),
+            "column {} is not pinned at the first step",
+            column
+        );
+    }
+}
+
+#[test]
+fn merkle_path_with_evil_capacity_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let key_idx = 3;
+    let topic = Topic::from(TOPIC);
+    let message = Rescue::hash(MESSAGE);
+    let key_path = access_set.get_key_path(key_idx).unwrap();
+    let prover = SemaphoreProver::default();
+
+    // hash the identity starting from capacity (99, 0, 0, 0) instead of (8, 0, 0, 0); this
+    // results in a different leaf for the same identity secrets, and thus, the same identity
+    // could be registered under many public keys
+    let mut trace = prover
+        .build_trace(&identities[key_idx], key_idx, topic, 0, message, &key_path)
+        .unwrap();
+    trace.set(0, 0, Felt::new(99));
+    let mut state = vec![Felt::ZERO; trace.width()];
+    for step in 0..trace.length() - 1 {
+        trace.read_row_into(step, &mut state);
+        update_state(step, &mut state, key_idx, message.into(), &key_path[1..]);
+        for (i, &value) in state.iter().enumerate() {
+            trace.set(i, step + 1, value);
+        }
+    }
+
+    // build an access set in which the evil leaf replaces the honest one
+    let evil_leaf = Digest::new([
+        trace.get(4, 2 * HASH_CYCLE_LEN - 1),
+        trace.get(5, 2 * HASH_CYCLE_LEN - 1),
+        trace.get(6, 2 * HASH_CYCLE_LEN - 1),
+        trace.get(7, 2 * HASH_CYCLE_LEN - 1),
+    ]);
+    let mut leaves = access_set.descriptor().leaves;
+    leaves[key_idx] = evil_leaf;
+    let evil_set = AccessSet::from_leaves(leaves);
+
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    assert_eq!(evil_set.root(), pub_inputs.tree_root);
+    assert_eq!(
+        identities[key_idx].get_nullifier(topic.digest()),
+        pub_inputs.nullifier
+    );
+
+    // the evil trace violates only the Merkle capacity assertion
+    let air = build_air(&prover, &trace);
+    assert_eq!(vec!["merkle capacity [15]"], failure_names(&air, &trace));
+
+    // a proof of the evil trace is rejected by the verifier
+    assert_evil_proof_is_rejected(&prover, trace);
+}
+
+// INDEX BITS
+// ================================================================================================
+
+#[test]
+fn index_accumulator_reconstructs_key_index() {
+    let (identities, access_set) = build_access_set(8);
+    let topic = Topic::from(TOPIC);
+    let message = Rescue::hash(MESSAGE);
+    let prover = SemaphoreProver::default();
+    for (key_idx, identity) in identities.iter().enumerate() {
+        let key_path = access_set.get_key_path(key_idx).unwrap();
+        let trace = prover
+            .build_trace(identity, key_idx, topic, 0, message, &key_path)
+            .unwrap();
+        assert_eq!(Felt::new(16 + 2 * key_idx as u64), trace.get(25, 0));
+        assert_eq!(Felt::ONE, trace.get(25, get_root_step(access_set.depth())));
+    }
+}
+
+#[test]
+fn signals_can_be_made_for_all_index_bit_patterns() {
+    // the prover checks constraint degrees in debug builds; keys at the first and the last
+    // indexes result in index bit columns which are all zeros and all ones respectively
+    for &num_keys in [8, 128].iter() {
+        let (identities, access_set) = build_access_set(num_keys);
+        for &key_idx in [0, num_keys / 2 + 1, num_keys - 1].iter() {
+            let signal = access_set
+                .make_signal(
+                    &identities[key_idx],
+                    TOPIC,
+                    MESSAGE,
+                    &SemaphoreProver::default(),
+                )
+                .unwrap();
+            assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_ok());
+        }
+    }
+}
+
+#[test]
+fn index_bits_must_not_change_within_hash_cycle() {
+    let (identities, access_set) = build_access_set(8);
+    let key_idx = 3;
+    let topic = Topic::from(TOPIC);
+    let message = Rescue::hash(MESSAGE);
+    let key_path = access_set.get_key_path(key_idx).unwrap();
+    let prover = SemaphoreProver::default();
+
+    let mut trace = prover
+        .build_trace(&identities[key_idx], key_idx, topic, 0, message, &key_path)
+        .unwrap();
+    trace.set(24, 2 * HASH_CYCLE_LEN + 3, Felt::ZERO);
+    let air = build_air(&prover, &trace);
+    assert_eq!(
+        vec!["index bit [25]", "index bit [25]"],
+        failure_names(&air, &trace)
+    );
+}
+
+// TRACE CONSTRUCTION
+// ================================================================================================
+
+#[test]
+fn trace_cannot_be_built_from_empty_path() {
+    let (identities, _) = build_access_set(8);
+    let result = SemaphoreProver::default().build_trace(
+        &identities[0],
+        0,
+        Topic::from(TOPIC),
+        0,
+        Rescue::hash(MESSAGE),
+        &[],
+    );
+    assert!(matches!(result, Err(SemaphoreError::EmptyMerklePath)));
+}
+
+// TRACE DEBUGGER
+// ================================================================================================
+
+#[test]
+fn debug_trace_accepts_honest_traces() {
+    let (identities, access_set) = build_access_set(8);
+    let topic = Topic::from(TOPIC);
+    let message = Rescue::hash(MESSAGE);
+    let prover = SemaphoreProver::default();
+    for (key_idx, identity) in identities.iter().enumerate() {
+        let key_path = access_set.get_key_path(key_idx).unwrap();
+        let trace = prover
+            .build_trace(identity, key_idx, topic, 0, message, &key_path)
+            .unwrap();
+        let air = build_air(&prover, &trace);
+        assert_eq!(Vec::<String>::new(), failure_names(&air, &trace));
+    }
+}
+
+#[test]
+fn debug_trace_names_failing_constraints() {
+    let (identities, access_set) = build_access_set(8);
+    let key_idx = 3;
+    let topic = Topic::from(TOPIC);
+    let message = Rescue::hash(MESSAGE);
+    let key_path = access_set.get_key_path(key_idx).unwrap();
+    let prover = SemaphoreProver::default();
+
+    // the evil trace is consistent with its own public inputs except for the nullifier capacity
+    let trace = build_evil_trace(&prover, &identities[key_idx], key_idx, &key_path);
+    let air = build_air(&prover, &trace);
+    let failures = debug_trace(&air, &trace);
+    assert_eq!(1, failures.len());
+    assert_eq!(ConstraintKind::Assertion, failures[0].kind);
+    assert_eq!("nullifier capacity [12]", failures[0].name);
+    assert_eq!(0, failures[0].step);
+    assert_eq!(Felt::new(8), failures[0].expected);
+    assert_eq!(Felt::new(1), failures[0].actual);
+
+    // changing the nullifier secret in the nullifier section breaks the key comparison at step 0
+    // and the first round of nullifier hashing
+    let mut trace = prover
+        .build_trace(&identities[key_idx], key_idx, topic, 0, message, &key_path)
+        .unwrap();
+    trace.set(17, 0, trace.get(17, 0) + Felt::ONE);
+    let air = build_air(&prover, &trace);
+    let names = failure_names(&air, &trace);
+    assert!(names.contains(&"key cmp [27]".to_string()));
+    assert!(names.contains(&"nullifier hash round [12]".to_string()));
+
+    // failing to reset the capacity of the hash state at the start of the second hash cycle
+    // breaks the capacity reset constraint at the last step of the first cycle
+    let mut trace = prover
+        .build_trace(&identities[key_idx], key_idx, topic, 0, message, &key_path)
+        .unwrap();
+    trace.set(0, HASH_CYCLE_LEN, Felt::new(7));
+    let air = build_air(&prover, &trace);
+    let failures = debug_trace(&air, &trace);
+    assert_eq!("merkle capacity reset [0]", failures[0].name);
+    assert_eq!(HASH_CYCLE_LEN - 1, failures[0].step);
+    assert_eq!(Felt::new(1), failures[0].actual);
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds an honest trace, and then re-computes the nullifier section of the trace (and the key
+/// share which depends on it) starting from capacity (1, 0, 0, 0) instead of (8, 0, 0, 0).
+fn build_evil_trace(
+    prover: &SemaphoreProver,
+    identity: &Identity,
+    key_idx: usize,
+    key_path: &[Digest],
+) -> TraceTable<Felt> {
+    let topic = Topic::from(TOPIC);
+    let message = Rescue::hash(MESSAGE);
+    let mut trace = prover
+        .build_trace(identity, key_idx, topic, 0, message, key_path)
+        .unwrap();
+
+    let mut state = [Felt::ZERO; 12];
+    for (i, value) in state.iter_mut().enumerate() {
+        *value = trace.get(12 + i, 0);
+    }
+    state[0] = Felt::new(1);
+    for step in 0..HASH_CYCLE_LEN {
+        for (i, &value) in state.iter().enumerate() {
+            trace.set(12 + i, step, value);
+        }
+        if step < NUM_HASH_ROUNDS {
+            Rescue::apply_round(&mut state, step);
+        }
+    }
+
+    // the slopes of the key share are taken from the nullifier hash output, and thus, the key
+    // share columns must be updated as well; the key share does not change after step 8
+    let message: [Felt; 4] = message.into();
+    let x = get_key_share_x(&message);
+    for i in 0..8 {
+        let slope = if i < 4 { state[8 + i] } else { state[i - 4] };
+        let key_share = trace.get(28 + i, 0) + slope * x;
+        for step in HASH_CYCLE_LEN..trace.length() {
+            trace.set(28 + i, step, key_share);
+        }
+    }
+
+    trace
+}
+
+/// Builds Semaphore AIR against public inputs read from the provided trace.
+fn build_air(prover: &SemaphoreProver, trace: &TraceTable<Felt>) -> SemaphoreAir {
+    SemaphoreAir::new(
+        TraceInfo::new(trace.width(), trace.length()),
+        prover.get_pub_inputs(trace),
+        prover.options().clone(),
+    )
+}
+
+fn failure_names(air: &SemaphoreAir, trace: &TraceTable<Felt>) -> Vec<String> {
+    debug_trace(air, trace)
+        .into_iter()
+        .map(|failure| failure.name)
+        .collect()
+}
+
+/// Proves the provided trace against [RelaxedAir], and checks that the proof is valid for
+/// [RelaxedAir] but is rejected when verified against Semaphore AIR.
+///
+/// The prover checks traces against the AIR in debug builds, and thus, an invalid trace cannot
+/// be proven against Semaphore AIR directly.
+fn assert_evil_proof_is_rejected(prover: &SemaphoreProver, trace: TraceTable<Felt>) {
+    let relaxed_inputs = prover.get_pub_inputs(&trace);
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    let proof = RelaxedProver(prover.clone()).prove(trace).unwrap();
+    assert!(winterfell::verify::<RelaxedAir>(proof.clone(), relaxed_inputs).is_ok());
+    assert!(winterfell::verify::<SemaphoreAir>(proof, pub_inputs).is_err());
+}
+
+/// Semaphore AIR without the assertions which pin hash capacities at the first step.
+pub(super) struct RelaxedAir(SemaphoreAir);
+
+impl Air for RelaxedAir {
+    type BaseField = Felt;
+    type PublicInputs = PublicInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        Self(SemaphoreAir::new(trace_info, pub_inputs, options))
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Felt>> {
+        self.0.get_periodic_column_values()
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Felt>> {
+        self.0
+            .get_assertions()
+            .into_iter()
+            .filter(|a| !(a.first_step() == 0 && matches!(a.register(), 0..=3 | 12..=15)))
+            .collect()
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Felt>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn context(&self) -> &AirContext<Felt> {
+        self.0.context()
+    }
+}
+
+/// Semaphore prover which generates proofs against [RelaxedAir].
+struct RelaxedProver(SemaphoreProver);
+
+impl Prover for RelaxedProver {
+    type BaseField = Felt;
+    type Air = RelaxedAir;
+    type Trace = TraceTable<Felt>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> PublicInputs {
+        self.0.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+}
@@ -0,0 +1,94 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    debug_trace, Felt, FieldElement, Hasher, Identity, PrivKey, Prover, PubKey, Rescue,
+    SemaphoreAir, SemaphoreProver, Topic, Trace, HASH_CYCLE_LEN,
+};
+use winterfell::{Air, TraceInfo};
+
+// IDENTITY COMMITMENT
+// ================================================================================================
+
+#[test]
+fn pub_key_is_hash_of_identity_secret_hash() {
+    let (identities, _) = build_access_set(8);
+    for identity in identities.iter() {
+        let secret_hash = Rescue::merge(&[
+            identity.nullifier_secret().into(),
+            identity.trapdoor().into(),
+        ]);
+        let expected: [Felt; 4] = Rescue::merge(&[secret_hash, [Felt::ZERO; 4].into()]).into();
+        assert_eq!(expected, identity.pub_key().elements());
+    }
+}
+
+#[test]
+fn trapdoor_changes_pub_key_but_not_nullifier() {
+    let (identities, _) = build_access_set(8);
+    let mut trapdoor = identities[3].trapdoor();
+    trapdoor[1] += Felt::ONE;
+    let other = Identity::new(identities[3].nullifier_secret(), trapdoor);
+
+    let topic = Rescue::hash(TOPIC.as_bytes());
+    assert_eq!(
+        identities[3].get_nullifier(topic),
+        other.get_nullifier(topic)
+    );
+    assert_ne!(
+        identities[3].pub_key().elements(),
+        other.pub_key().elements()
+    );
+}
+
+#[test]
+fn identity_can_be_parsed_from_string() {
+    let (identities, _) = build_access_set(8);
+    let identity = Identity::parse(&identities[6].to_string()).unwrap();
+    assert_eq!(identities[6], identity);
+}
+
+#[test]
+fn commitment_cycle_must_hash_with_zero() {
+    let (identities, access_set) = build_access_set(8);
+    let key_idx = 2;
+    let topic = Topic::from(TOPIC);
+    let message = Rescue::hash(MESSAGE);
+    let key_path = access_set.get_key_path(key_idx).unwrap();
+    let prover = SemaphoreProver::default();
+
+    let mut trace = prover
+        .build_trace(&identities[key_idx], key_idx, topic, 0, message, &key_path)
+        .unwrap();
+    trace.set(9, HASH_CYCLE_LEN, Felt::ONE);
+    let air = SemaphoreAir::new(
+        TraceInfo::new(trace.width(), trace.length()),
+        prover.get_pub_inputs(&trace),
+        prover.options().clone(),
+    );
+    let failures = debug_trace(&air, &trace);
+    assert_eq!("commitment padding [23]", failures[0].name);
+    assert_eq!(HASH_CYCLE_LEN, failures[0].step);
+}
+
+// MIGRATION
+// ================================================================================================
+
+#[test]
+fn migrated_identity_keeps_nullifiers() {
+    let priv_key = PrivKey::new([Felt::new(3), Felt::new(5), Felt::new(7), Felt::new(9)]);
+    let trapdoor = [Felt::new(2), Felt::new(4), Felt::new(6), Felt::new(8)];
+    let identity = Identity::from_priv_key(&priv_key, trapdoor);
+
+    // the private key becomes the nullifier secret, and the trapdoor is the provided one
+    assert_eq!(priv_key.elements(), identity.nullifier_secret());
+    assert_eq!(trapdoor, identity.trapdoor());
+
+    // nullifiers of the migrated identity are the same as the legacy nullifiers, i.e.,
+    // hash(priv_key, topic), but the public key is not the same as the legacy public key
+    let topic = Rescue::hash(TOPIC.as_bytes());
+    let legacy_nullifier = Rescue::merge(&[priv_key.elements().into(), topic]);
+    assert_eq!(legacy_nullifier, identity.get_nullifier(topic));
+    assert_ne!(
+        PubKey::from_priv_key(&priv_key).elements(),
+        identity.pub_key().elements()
+    );
+}
@@ -0,0 +1,256 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    get_empty_leaf, AccessSetDescriptor, Felt, Identity, InMemoryNullifierStore,
+    IncrementalAccessSet, PubKey, SemaphoreError, SemaphoreProver,
+};
+
+// APPENDING KEYS
+// ================================================================================================
+
+#[test]
+fn incremental_access_set_matches_access_set() {
+    let (identities, access_set) = build_access_set(5);
+
+    let mut incremental_set = IncrementalAccessSet::new(3, 8).unwrap();
+    for (i, identity) in identities.iter().enumerate() {
+        assert_eq!(i, incremental_set.append(identity.pub_key()).unwrap());
+    }
+
+    // a tree of depth 3 with 5 keys is the same as an access set padded with 3 empty leaves
+    assert_eq!(5, incremental_set.num_keys());
+    assert_eq!(access_set.depth(), incremental_set.depth());
+    assert_eq!(access_set.root(), incremental_set.root());
+    for key_idx in 0..5 {
+        assert_eq!(
+            access_set.get_key_path(key_idx).unwrap(),
+            incremental_set.get_key_path(key_idx).unwrap()
+        );
+    }
+
+    // the root of the empty tree and every root after an append are in the history
+    assert_eq!(6, incremental_set.root_history().count());
+    assert!(incremental_set.is_known_root(&access_set.root()));
+}
+
+#[test]
+fn key_cannot_be_appended_to_full_access_set() {
+    let (identities, _) = build_access_set(3);
+    let mut incremental_set = IncrementalAccessSet::new(1, 4).unwrap();
+    incremental_set.append(identities[0].pub_key()).unwrap();
+    incremental_set.append(identities[1].pub_key()).unwrap();
+    assert_eq!(
+        SemaphoreError::InvalidSetSize(3),
+        incremental_set.append(identities[2].pub_key()).unwrap_err()
+    );
+}
+
+#[test]
+fn empty_leaf_cannot_be_appended() {
+    let mut incremental_set = IncrementalAccessSet::new(2, 4).unwrap();
+    assert_eq!(
+        SemaphoreError::InvalidPubKey,
+        incremental_set
+            .append(PubKey(get_empty_leaf()))
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn access_set_depth_and_history_size_are_checked() {
+    assert_eq!(
+        SemaphoreError::InvalidDepth(0),
+        IncrementalAccessSet::new(0, 4).err().unwrap()
+    );
+    assert_eq!(
+        SemaphoreError::InvalidDepth(33),
+        IncrementalAccessSet::new(33, 4).err().unwrap()
+    );
+    assert_eq!(
+        SemaphoreError::InvalidHistorySize(0),
+        IncrementalAccessSet::new(2, 0).err().unwrap()
+    );
+}
+
+#[test]
+fn incremental_access_set_matches_access_set_after_updates() {
+    let (identities, mut access_set) = build_access_set(6);
+    let new_identity = Identity::new([Felt::new(101); 4], [Felt::new(102); 4]);
+
+    let mut incremental_set = IncrementalAccessSet::new(3, 8).unwrap();
+    for identity in identities.iter() {
+        incremental_set.append(identity.pub_key()).unwrap();
+    }
+
+    assert_eq!(
+        access_set.remove(4).unwrap(),
+        incremental_set.remove(4).unwrap()
+    );
+    assert_eq!(
+        access_set.replace(1, new_identity.pub_key()).unwrap(),
+        incremental_set.replace(1, new_identity.pub_key()).unwrap()
+    );
+    assert_eq!(access_set.root(), incremental_set.root());
+
+    // a removed slot is not reused by later appends
+    assert_eq!(6, incremental_set.append(identities[4].pub_key()).unwrap());
+}
+
+// ROOT HISTORY
+// ================================================================================================
+
+#[test]
+fn signal_is_valid_while_its_root_is_in_history() {
+    let prover = SemaphoreProver::default();
+    let (identities, _) = build_access_set(4);
+
+    // keep only the current and the previous roots
+    let mut incremental_set = IncrementalAccessSet::new(3, 2).unwrap();
+    incremental_set.append(identities[0].pub_key()).unwrap();
+    incremental_set.append(identities[1].pub_key()).unwrap();
+    let signal = incremental_set
+        .make_signal(&identities[1], TOPIC, MESSAGE, &prover)
+        .unwrap();
+
+    // after one more key is appended, the signal is made against the previous root
+    incremental_set.append(identities[2].pub_key()).unwrap();
+    assert!(incremental_set
+        .verify_signal(TOPIC, MESSAGE, signal.clone())
+        .is_ok());
+
+    // after another key is appended, the root of the signal is evicted from the history
+    incremental_set.append(identities[3].pub_key()).unwrap();
+    assert_eq!(
+        SemaphoreError::UnknownRoot(signal.root),
+        incremental_set
+            .verify_signal(TOPIC, MESSAGE, signal)
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn removed_key_cannot_signal_against_previous_roots() {
+    let prover = SemaphoreProver::default();
+    let (identities, _) = build_access_set(4);
+
+    let mut incremental_set = IncrementalAccessSet::new(3, 8).unwrap();
+    for identity in identities.iter() {
+        incremental_set.append(identity.pub_key()).unwrap();
+    }
+    let signals = (0..2)
+        .map(|i| {
+            incremental_set
+                .make_signal(&identities[i], TOPIC, MESSAGE, &prover)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // removing and replacing a key revokes all previous roots, including the root of the signal
+    incremental_set.remove(0).unwrap();
+    assert_eq!(1, incremental_set.root_history().count());
+    assert_eq!(
+        SemaphoreError::UnknownRoot(signals[0].root),
+        incremental_set
+            .verify_signal(TOPIC, MESSAGE, signals[0].clone())
+            .unwrap_err()
+    );
+
+    incremental_set.append(identities[0].pub_key()).unwrap();
+    let root = incremental_set.root();
+    incremental_set
+        .replace(
+            1,
+            Identity::new([Felt::new(101); 4], [Felt::new(102); 4]).pub_key(),
+        )
+        .unwrap();
+    assert!(!incremental_set.is_known_root(&root));
+    assert!(incremental_set
+        .verify_signal(TOPIC, MESSAGE, signals[1].clone())
+        .is_err());
+}
+
+#[test]
+fn nullifier_does_not_depend_on_root() {
+    let prover = SemaphoreProver::default();
+    let (identities, _) = build_access_set(2);
+    let mut store = InMemoryNullifierStore::new();
+
+    let mut incremental_set = IncrementalAccessSet::new(2, 4).unwrap();
+    incremental_set.append(identities[0].pub_key()).unwrap();
+    let signal = incremental_set
+        .make_signal(&identities[0], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(incremental_set
+        .accept_signal(TOPIC, MESSAGE, signal, &mut store)
+        .is_ok());
+
+    // a signal made against a new root is a duplicate of the signal made against the old one
+    incremental_set.append(identities[1].pub_key()).unwrap();
+    let signal = incremental_set
+        .make_signal(&identities[0], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(matches!(
+        incremental_set.accept_signal(TOPIC, MESSAGE, signal, &mut store),
+        Err(SemaphoreError::DuplicateNullifier { .. })
+    ));
+}
+
+// DESCRIPTORS
+// ================================================================================================
+
+#[test]
+fn incremental_access_set_can_be_rebuilt_from_descriptor() {
+    let (identities, _) = build_access_set(6);
+    let mut incremental_set = IncrementalAccessSet::new(4, 8).unwrap();
+    for identity in identities[..5].iter() {
+        incremental_set.append(identity.pub_key()).unwrap();
+    }
+    incremental_set.remove(1).unwrap();
+
+    // the same access set with one more key appended
+    let mut expected_set = IncrementalAccessSet::new(4, 8).unwrap();
+    for identity in identities.iter() {
+        expected_set.append(identity.pub_key()).unwrap();
+    }
+    expected_set.remove(1).unwrap();
+
+    // a partially filled access set keeps its depth through binary and JSON encodings
+    let descriptor = incremental_set.descriptor();
+    for encoded in [
+        AccessSetDescriptor::from_bytes(&descriptor.to_bytes()).unwrap(),
+        AccessSetDescriptor::from_json(&descriptor.to_json()).unwrap(),
+    ]
+    .iter()
+    {
+        let mut loaded_set = IncrementalAccessSet::from_descriptor(encoded, 8).unwrap();
+        assert_eq!(descriptor, loaded_set.descriptor());
+        assert_eq!(16, loaded_set.capacity());
+        assert_eq!(
+            vec![&incremental_set.root()],
+            loaded_set.root_history().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            incremental_set.get_key_path(3).unwrap(),
+            loaded_set.get_key_path(3).unwrap()
+        );
+
+        // keys can be appended to the loaded access set
+        assert_eq!(5, loaded_set.append(identities[5].pub_key()).unwrap());
+        assert_eq!(expected_set.root(), loaded_set.root());
+    }
+
+    // a descriptor with a tampered root or too many leaves is rejected
+    let mut tampered = descriptor.clone();
+    tampered.root = identities[5].pub_key().0;
+    assert!(matches!(
+        IncrementalAccessSet::from_descriptor(&tampered, 8),
+        Err(SemaphoreError::RootMismatch { .. })
+    ));
+    let mut tampered = descriptor;
+    tampered.depth = 2;
+    assert_eq!(
+        SemaphoreError::InvalidSetSize(5),
+        IncrementalAccessSet::from_descriptor(&tampered, 8)
+            .err()
+            .unwrap()
+    );
+}
@@ -0,0 +1,192 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    AccessSetDescriptor, Felt, Identity, IncrementalAccessSet, PrivKey, PubKey, SemaphoreError,
+    SemaphoreProver, Signal, Topic, JSON_SCHEMA_VERSION,
+};
+use winter_utils::DeserializationError;
+
+// KEYS
+// ================================================================================================
+
+#[test]
+fn keys_can_be_read_from_json() {
+    let (identities, _) = build_access_set(1);
+    let pub_key = identities[0].pub_key();
+    let json = pub_key.to_json();
+    assert_eq!(
+        format!(
+            r#"{{"version":{},"key":"{}"}}"#,
+            JSON_SCHEMA_VERSION, pub_key
+        ),
+        json
+    );
+    assert_eq!(pub_key.0, PubKey::from_json(&json).unwrap().0);
+
+    let priv_key = PrivKey::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+    let json = priv_key.to_json();
+    assert_eq!(
+        format!(
+            r#"{{"version":{},"key":"{}"}}"#,
+            JSON_SCHEMA_VERSION, priv_key
+        ),
+        json
+    );
+    assert_eq!(
+        priv_key.elements(),
+        PrivKey::from_json(&json).unwrap().elements()
+    );
+
+    // whitespace and field order do not matter
+    let json = format!("{{\n  \"key\": \"{}\",\n  \"version\": 1\n}}\n", pub_key);
+    assert_eq!(pub_key.0, PubKey::from_json(&json).unwrap().0);
+}
+
+#[test]
+fn identity_can_be_read_from_json() {
+    let (identities, _) = build_access_set(2);
+    let json = identities[1].to_json();
+    assert_eq!(
+        format!(
+            r#"{{"version":{},"nullifier_secret":"{}","trapdoor":"{}"}}"#,
+            JSON_SCHEMA_VERSION,
+            PrivKey::new(identities[1].nullifier_secret()),
+            PrivKey::new(identities[1].trapdoor())
+        ),
+        json
+    );
+    assert_eq!(identities[1], Identity::from_json(&json).unwrap());
+
+    // an identity with a non-canonical secret is rejected
+    let json = json.replacen(
+        &PrivKey::new(identities[1].trapdoor()).to_string(),
+        &"ff".repeat(32),
+        1,
+    );
+    assert!(Identity::from_json(&json).is_err());
+
+    // an identity of an unsupported schema version is rejected
+    let json = identities[1].to_json().replacen(
+        &format!(r#""version":{}"#, JSON_SCHEMA_VERSION),
+        r#""version":99"#,
+        1,
+    );
+    assert!(Identity::from_json(&json).is_err());
+}
+
+// SIGNALS
+// ================================================================================================
+
+#[test]
+fn signal_can_be_verified_after_json_encoding() {
+    let prover = SemaphoreProver::default();
+    let (identities, access_set) = build_access_set(4);
+
+    let signal = access_set
+        .make_signal(&identities[1], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    let json = signal.to_json();
+    assert!(json.contains(r#""key_share":null"#));
+    let decoded = Signal::from_json(&json).unwrap();
+    assert_eq!(signal.nullifier, decoded.nullifier);
+    assert_eq!(signal.proof.to_bytes(), decoded.proof.to_bytes());
+    assert!(access_set.verify_signal(TOPIC, MESSAGE, decoded).is_ok());
+
+    let topic = Topic::from(TOPIC).with_slashing();
+    let signal = access_set
+        .make_signal(&identities[1], topic, MESSAGE, &prover)
+        .unwrap();
+    let decoded = Signal::from_json(&signal.to_json()).unwrap();
+    assert_eq!(signal.key_share, decoded.key_share);
+    assert!(access_set.verify_signal(topic, MESSAGE, decoded).is_ok());
+}
+
+// ACCESS SETS
+// ================================================================================================
+
+#[test]
+fn access_set_descriptor_can_be_read_from_json() {
+    let (identities, mut access_set) = build_access_set(5);
+    access_set.remove(2).unwrap();
+
+    let descriptor = access_set.descriptor();
+    assert_eq!(access_set.root(), descriptor.root);
+    assert_eq!(3, descriptor.depth);
+    assert_eq!(5, descriptor.leaves.len());
+    assert_eq!(
+        descriptor,
+        AccessSetDescriptor::from_json(&descriptor.to_json()).unwrap()
+    );
+
+    // an incremental access set with the same keys has the same descriptor
+    let mut incremental_set = IncrementalAccessSet::new(3, 1).unwrap();
+    for identity in identities.iter() {
+        incremental_set.append(identity.pub_key()).unwrap();
+    }
+    incremental_set.remove(2).unwrap();
+    assert_eq!(descriptor, incremental_set.descriptor());
+}
+
+// MALFORMED INPUT
+// ================================================================================================
+
+#[test]
+fn malformed_json_is_rejected() {
+    let (identities, _) = build_access_set(1);
+    let key = identities[0].pub_key().to_string();
+
+    assert_invalid_value(
+        &format!(r#"{{"version":2,"key":"{}"}}"#, key),
+        "unsupported JSON schema version 2",
+    );
+    assert_invalid_value(
+        &format!(r#"{{"key":"{}"}}"#, key),
+        "invalid JSON: missing field `version`",
+    );
+    assert_invalid_value(
+        &format!(r#"{{"version":1,"key":"{}"}} x"#, key),
+        "invalid JSON: trailing characters",
+    );
+    assert_invalid_value(
+        &format!(r#"{{"version":1,"version":1,"key":"{}"}}"#, key),
+        "invalid JSON: duplicate field `version`",
+    );
+    assert_invalid_value(
+        &format!(r#"{{"version":1,"key":"{}"}}"#, &key[2..]),
+        "invalid field 'key': key must consist of 32 bytes, but was 31 bytes",
+    );
+    assert_invalid_value(
+        &format!(r#"{{"version":1,"key":"{}"}}"#, "ff".repeat(32)),
+        "invalid field 'key': key element 0 is greater than or equal to the field modulus",
+    );
+    assert_invalid_value(
+        r#"{"version":1,"key":"not hex"}"#,
+        "invalid field 'key': is not a valid hex string",
+    );
+    assert_eq!(
+        Err(SemaphoreError::Deserialization(
+            DeserializationError::UnexpectedEOF
+        )),
+        PubKey::from_json(&format!(r#"{{"version":1,"key":"{}""#, key)).map(|p| p.0)
+    );
+
+    let signal =
+        r#"{"version":1,"root":"KEY","depth":3,"nullifier":"KEY","key_share":null,"proof":"A==="}"#;
+    match Signal::from_json(&signal.replace("KEY", &key)) {
+        Err(SemaphoreError::Deserialization(DeserializationError::InvalidValue(err))) => {
+            assert_eq!("invalid field 'proof': is not a valid base64 string", err)
+        }
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn assert_invalid_value(json: &str, expected: &str) {
+    match PubKey::from_json(json) {
+        Err(SemaphoreError::Deserialization(DeserializationError::InvalidValue(err))) => {
+            assert!(err.starts_with(expected), "unexpected error: {}", err)
+        }
+        result => panic!("unexpected result: {:?}", result.map(|p| p.0)),
+    }
+}
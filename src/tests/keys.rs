@@ -0,0 +1,106 @@
+use super::build_access_set;
+use crate::{Felt, Identity, KeyParseError, PrivKey, PubKey, SemaphoreError};
+use std::convert::TryFrom;
+use winterfell::math::{FieldElement, StarkField};
+
+// KEY PARSING
+// ================================================================================================
+
+#[test]
+fn keys_can_be_parsed_from_strings_and_bytes() {
+    let (identities, _) = build_access_set(1);
+    let pub_key = identities[0].pub_key();
+    let parsed: PubKey = pub_key.to_string().parse().unwrap();
+    assert_eq!(pub_key.0, parsed.0);
+    let parsed = PubKey::try_from(Felt::elements_as_bytes(&pub_key.elements())).unwrap();
+    assert_eq!(pub_key.0, parsed.0);
+
+    let priv_key = PrivKey::new([Felt::new(1), Felt::new(2), Felt::new(3), -Felt::ONE]);
+    let parsed: PrivKey = priv_key.to_string().parse().unwrap();
+    assert_eq!(priv_key.elements(), parsed.elements());
+    let parsed = PrivKey::try_from(Felt::elements_as_bytes(&priv_key.elements())).unwrap();
+    assert_eq!(priv_key.elements(), parsed.elements());
+}
+
+#[test]
+fn keys_of_invalid_length_are_rejected() {
+    let (identities, _) = build_access_set(1);
+    let key = identities[0].pub_key().to_string();
+
+    for &len in [0, 62, 66].iter() {
+        let key = format!("{:0<66}", key);
+        assert_eq!(
+            Err(KeyParseError::InvalidLength(len / 2)),
+            key[..len].parse::<PubKey>().map(|k| k.0)
+        );
+        assert_eq!(
+            Err(KeyParseError::InvalidLength(len / 2)),
+            key[..len].parse::<PrivKey>().map(|k| k.elements())
+        );
+    }
+
+    assert_eq!(
+        Err(KeyParseError::InvalidHex),
+        key.replace('0', "x").parse::<PubKey>().map(|k| k.0)
+    );
+    assert_eq!(
+        Err(KeyParseError::InvalidHex),
+        key[1..].parse::<PrivKey>().map(|k| k.elements())
+    );
+}
+
+#[test]
+fn keys_with_non_canonical_elements_are_rejected() {
+    for &index in [0, 3].iter() {
+        let mut key_bytes = [0u8; 32];
+        key_bytes[index * 8..(index + 1) * 8].copy_from_slice(&Felt::MODULUS.to_le_bytes());
+        assert_eq!(
+            Err(KeyParseError::NonCanonicalElement(index)),
+            PubKey::try_from(&key_bytes[..]).map(|k| k.0)
+        );
+
+        // values above the modulus are rejected as well
+        key_bytes[index * 8..(index + 1) * 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            Err(KeyParseError::NonCanonicalElement(index)),
+            hex::encode(key_bytes)
+                .parse::<PrivKey>()
+                .map(|k| k.elements())
+        );
+    }
+}
+
+#[test]
+fn parse_returns_error_on_invalid_key() {
+    let err = PubKey::parse(&"00".repeat(31)).unwrap_err();
+    assert_eq!(
+        SemaphoreError::InvalidKey(KeyParseError::InvalidLength(31)),
+        err
+    );
+    assert_eq!(
+        "invalid key encoding: key must consist of 32 bytes, but was 31 bytes",
+        err.to_string()
+    );
+    assert_eq!(
+        SemaphoreError::InvalidKey(KeyParseError::InvalidHex),
+        PrivKey::parse("xyz").unwrap_err()
+    );
+}
+
+#[test]
+fn parse_returns_error_on_invalid_identity() {
+    let err = Identity::parse(&"00".repeat(63)).unwrap_err();
+    assert_eq!(
+        "invalid identity encoding: identity must consist of 64 bytes, but was 63 bytes",
+        err.to_string()
+    );
+
+    let mut identity_bytes = [0u8; 64];
+    identity_bytes[40..48].copy_from_slice(&u64::MAX.to_le_bytes());
+    let err = Identity::parse(&hex::encode(identity_bytes)).unwrap_err();
+    assert_eq!(
+        "invalid identity encoding: trapdoor: key element 1 is greater than or equal to the \
+         field modulus",
+        err.to_string()
+    );
+}
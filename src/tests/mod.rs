@@ -0,0 +1,62 @@
+use super::{AccessSet, Felt, Identity, PubKey};
+use std::{fs, path::PathBuf};
+
+mod access_set;
+mod air;
+mod identity;
+mod incremental;
+mod json;
+mod keys;
+mod nullifier;
+mod policy;
+mod poll;
+mod rate_limit;
+mod registry;
+mod serialization;
+mod signal;
+mod slashing;
+mod store;
+mod topic;
+mod underconstrained;
+
+// TEST HELPERS
+// ================================================================================================
+
+/// A topic used for signals made in tests.
+const TOPIC: &str = "The Winter is Coming...";
+
+/// A message contained in signals made in tests.
+const MESSAGE: &[u8] = b"Yes";
+
+/// Returns `num_keys` deterministically generated identities together with an access set built
+/// from the corresponding public keys.
+fn build_access_set(num_keys: usize) -> (Vec<Identity>, AccessSet) {
+    let identities = (0..num_keys as u64)
+        .map(|i| {
+            Identity::new(
+                [
+                    Felt::new(8 * i + 1),
+                    Felt::new(8 * i + 2),
+                    Felt::new(8 * i + 3),
+                    Felt::new(8 * i + 4),
+                ],
+                [
+                    Felt::new(8 * i + 5),
+                    Felt::new(8 * i + 6),
+                    Felt::new(8 * i + 7),
+                    Felt::new(8 * i + 8),
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+    let pub_keys = identities.iter().map(PubKey::new).collect::<Vec<_>>();
+    (identities, AccessSet::new(pub_keys).unwrap())
+}
+
+/// Returns a path to a file in the temporary directory which is unique to the specified test and
+/// the current process; any leftover file from a previous run is removed.
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("semaphore-{}-{}", std::process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
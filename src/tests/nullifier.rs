@@ -0,0 +1,139 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    compute_nullifier, AccessSet, Digest, Felt, FieldElement, Hasher, HasherDigest, Identity,
+    PrivKey, PubKey, Rescue, SemaphoreProver, StarkField, Topic, Trace,
+};
+use rand_utils::rand_array;
+
+// KNOWN ANSWERS
+// ================================================================================================
+
+/// Legacy public keys of the access set from the puzzle.
+const PUZZLE_PUB_KEYS: [&str; 8] = [
+    "04f6d8d05f52012c0a705c1e0dcb1ff64ba0842c8c14f1f0f18e95254bdcfbea",
+    "af84cf58cb71709c5a94750e69f9cbad0244d6c8e437f4e822c58f0c45c69ea0",
+    "964650c5645e30b1ff74574a6fc4cdb78eaa1be3dfd43f01050b1b0e41d4db36",
+    "d5a494b415c20d7d00fbace4f725b596da7c646d80e622956d7f09eebc93fef9",
+    "9d7083734388833056ae25382dbcfb39b6a1ee78a6d63f136d83400569adc319",
+    "a7ae57a7b2c60871e86d152e9e712ab5a3630f6183a7c1d07ba4429fead88018",
+    "1995c40e8e46a009b0d61d89634f3c959d13322ef3a84b410a811eb4fc06d08b",
+    "cf855bce16bb7b37f874324da9f72dd0d0e6f6e9f9e29100f66c7b57c6895ef5",
+];
+
+/// Private key from the puzzle; corresponds to the 4th public key above.
+const PUZZLE_PRIV_KEY: &str = "86475af21e4445b71bfa496416ee2d0765946bd3a854a77fe07db53c7994d0a5";
+
+/// Nullifier of the puzzle private key on the puzzle topic; this is also the nullifier of the
+/// identity migrated from this key.
+const PUZZLE_NULLIFIER: &str = "fa9f5e2287b26f5fc91643a65ecfebbf308c6230283cd5c2a6a57ffe8a60e19d";
+
+#[test]
+fn nullifier_known_answers() {
+    let identity = Identity::from_priv_key(&PrivKey::parse(PUZZLE_PRIV_KEY).unwrap(), rand_array());
+    let topic = Rescue::hash(TOPIC.as_bytes());
+    assert_eq!(PUZZLE_NULLIFIER, to_hex(identity.get_nullifier(topic)));
+
+    let nullifier = compute_nullifier([Felt::ZERO; 4], [Felt::ZERO; 4], 0);
+    assert_eq!(
+        "22ec46428291431d76a3713549fb8561517cb06a018c9050eeb5a1b5b859c0d6",
+        to_hex(nullifier)
+    );
+
+    let nullifier_secret = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+    let topic = Rescue::hash(b"hello");
+    assert_eq!(
+        "8c089502352ab09608dd123c64436816db910deb3a330ba8921a139d27c1fa96",
+        to_hex(compute_nullifier(nullifier_secret, topic.into(), 0))
+    );
+
+    let m = Felt::MODULUS;
+    let nullifier_secret = [
+        Felt::new(m - 1),
+        Felt::new(m - 2),
+        Felt::new(m - 3),
+        Felt::new(m - 4),
+    ];
+    let topic = [Felt::new(5), Felt::new(6), Felt::new(7), Felt::new(8)];
+    assert_eq!(
+        "cd4a5d175c998d5c91024edcbac68ce7534b3a42fb94dd332f85b0c33a37fef9",
+        to_hex(compute_nullifier(nullifier_secret, topic, 0))
+    );
+}
+
+#[test]
+fn legacy_pub_key_known_answer() {
+    let priv_key = PrivKey::parse(PUZZLE_PRIV_KEY).unwrap();
+    assert_eq!(
+        PUZZLE_PUB_KEYS[3],
+        PubKey::from_priv_key(&priv_key).to_string()
+    );
+}
+
+// CONSISTENCY
+// ================================================================================================
+
+#[test]
+fn nullifier_matches_rescue_merge() {
+    let (identities, _) = build_access_set(8);
+    for (i, identity) in identities.iter().enumerate() {
+        let topic = Rescue::hash(format!("topic {}", i).as_bytes());
+        let expected = Rescue::merge(&[identity.nullifier_secret().into(), topic]);
+        assert_eq!(expected, identity.get_nullifier(topic));
+    }
+}
+
+#[test]
+fn nullifier_matches_trace() {
+    let (identities, access_set) = build_access_set(8);
+    let topic = Rescue::hash(TOPIC.as_bytes());
+    let message = Rescue::hash(MESSAGE);
+    let prover = SemaphoreProver::default();
+    for (key_idx, identity) in identities.iter().enumerate() {
+        let key_path = access_set.get_key_path(key_idx).unwrap();
+        let trace = prover
+            .build_trace(identity, key_idx, Topic::new(topic), 0, message, &key_path)
+            .unwrap();
+        let nullifier = [
+            trace.get(16, 7),
+            trace.get(17, 7),
+            trace.get(18, 7),
+            trace.get(19, 7),
+        ];
+        assert_eq!(identity.get_nullifier(topic), nullifier.into());
+    }
+}
+
+#[test]
+fn puzzle_signal_has_canonical_nullifier() {
+    // replace the legacy public key of the puzzle private key with the public key of the
+    // migrated identity
+    let priv_key = PrivKey::parse(PUZZLE_PRIV_KEY).unwrap();
+    let identity = Identity::from_priv_key(&priv_key, rand_array());
+    let legacy_pub_key = PubKey::from_priv_key(&priv_key).to_string();
+    let access_set = AccessSet::new(
+        PUZZLE_PUB_KEYS
+            .iter()
+            .map(|&k| {
+                if k == legacy_pub_key {
+                    identity.pub_key()
+                } else {
+                    PubKey::parse(k).unwrap()
+                }
+            })
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+
+    let signal = access_set
+        .make_signal(&identity, TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+    assert_eq!(PUZZLE_NULLIFIER, to_hex(signal.nullifier));
+    assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_ok());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn to_hex(digest: Digest) -> String {
+    hex::encode(digest.as_bytes())
+}
@@ -0,0 +1,186 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    ProofOptionsBuilder, Prover, SecurityPreset, SemaphoreError, SemaphoreProver,
+    VerificationPolicy,
+};
+use winterfell::{FieldExtension, HashFunction};
+
+// VERIFICATION POLICY
+// ================================================================================================
+
+#[test]
+fn default_policy_accepts_default_signals() {
+    let (identities, access_set) = build_access_set(8);
+    let signal = access_set
+        .make_signal(&identities[2], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+    assert!(access_set.policy().check(&signal.proof).is_ok());
+    assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_ok());
+}
+
+#[test]
+fn default_prover_meets_default_policy_security() {
+    let prover = SemaphoreProver::default();
+    assert_eq!(32, prover.options().num_queries());
+    assert_eq!(8, prover.options().blowup_factor());
+    assert_eq!(0, prover.options().grinding_factor());
+
+    let (identities, access_set) = build_access_set(8);
+    let signal = access_set
+        .make_signal(&identities[2], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(signal.proof.security_level(true) >= VerificationPolicy::default().min_security_bits);
+}
+
+#[test]
+fn signal_with_low_security_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let options = ProofOptionsBuilder::from_preset(SecurityPreset::Fast96)
+        .num_queries(1)
+        .grinding_factor(0)
+        .build()
+        .unwrap();
+    let signal = access_set
+        .make_signal(
+            &identities[2],
+            TOPIC,
+            MESSAGE,
+            &SemaphoreProver::new(options),
+        )
+        .unwrap();
+
+    // the proof itself is valid, but it does not provide enough security
+    let err = access_set
+        .verify_signal(TOPIC, MESSAGE, signal)
+        .unwrap_err();
+    assert_eq!(
+        "proof violates verification policy: security level of 2 bits is below the minimum of 90 bits",
+        err.to_string()
+    );
+}
+
+#[test]
+fn signal_with_disallowed_options_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+
+    let options = ProofOptionsBuilder::from_preset(SecurityPreset::Fast96)
+        .hash_fn(HashFunction::Blake3_192)
+        .build()
+        .unwrap();
+    let signal = access_set
+        .make_signal(
+            &identities[2],
+            TOPIC,
+            MESSAGE,
+            &SemaphoreProver::new(options),
+        )
+        .unwrap();
+    let err = access_set
+        .verify_signal(TOPIC, MESSAGE, signal)
+        .unwrap_err();
+    assert_eq!(
+        "proof violates verification policy: hash function Blake3_192 is not allowed",
+        err.to_string()
+    );
+
+    let options = ProofOptionsBuilder::from_preset(SecurityPreset::Fast96)
+        .field_extension(FieldExtension::None)
+        .build()
+        .unwrap();
+    let signal = access_set
+        .make_signal(
+            &identities[2],
+            TOPIC,
+            MESSAGE,
+            &SemaphoreProver::new(options),
+        )
+        .unwrap();
+    let err = access_set
+        .verify_signal(TOPIC, MESSAGE, signal)
+        .unwrap_err();
+    assert_eq!(
+        "proof violates verification policy: field extension None is not allowed",
+        err.to_string()
+    );
+
+    // the same signal is accepted once the policy allows the options it was generated with
+    let options = ProofOptionsBuilder::from_preset(SecurityPreset::Fast96)
+        .hash_fn(HashFunction::Blake3_192)
+        .build()
+        .unwrap();
+    let signal = access_set
+        .make_signal(
+            &identities[2],
+            TOPIC,
+            MESSAGE,
+            &SemaphoreProver::new(options),
+        )
+        .unwrap();
+    let access_set = access_set.with_policy(VerificationPolicy {
+        hash_fns: vec![HashFunction::Blake3_192],
+        ..VerificationPolicy::default()
+    });
+    assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_ok());
+}
+
+#[test]
+fn invalid_proof_options_are_rejected() {
+    let builder = ProofOptionsBuilder::from_preset(SecurityPreset::Fast96);
+    let invalid = [
+        (
+            builder.clone().num_queries(0),
+            "number of queries must be between 1 and 128, but was 0",
+        ),
+        (
+            builder.clone().blowup_factor(4),
+            "blowup factor must be a power of two between 8 and 128, but was 4",
+        ),
+        (
+            builder.clone().blowup_factor(24),
+            "blowup factor must be a power of two between 8 and 128, but was 24",
+        ),
+        (
+            builder.clone().grinding_factor(33),
+            "grinding factor must be between 0 and 32, but was 33",
+        ),
+        (
+            builder.clone().fri_folding_factor(2),
+            "FRI folding factor must be a power of two between 4 and 16, but was 2",
+        ),
+        (
+            builder.fri_max_remainder_size(2048),
+            "FRI max remainder size must be a power of two between 32 and 1024, but was 2048",
+        ),
+    ];
+    for (builder, expected) in invalid.iter() {
+        assert_eq!(
+            SemaphoreError::InvalidProofOptions(expected.to_string()),
+            builder.clone().build().unwrap_err()
+        );
+    }
+}
+
+#[test]
+fn signal_exceeding_max_proof_size_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let signal = access_set
+        .make_signal(&identities[2], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+    let proof_size = signal.proof.to_bytes().len();
+
+    let access_set = access_set.with_policy(VerificationPolicy {
+        max_proof_size: proof_size - 1,
+        ..VerificationPolicy::default()
+    });
+    let err = access_set
+        .verify_signal(TOPIC, MESSAGE, signal)
+        .unwrap_err();
+    assert_eq!(
+        format!(
+            "proof violates verification policy: proof size of {} bytes exceeds the maximum of {} bytes",
+            proof_size,
+            proof_size - 1
+        ),
+        err.to_string()
+    );
+}
@@ -0,0 +1,330 @@
+use super::{build_access_set, TOPIC};
+use crate::{
+    Poll, SemaphoreError, SemaphoreProver, Topic, Transcript, VerificationPolicy,
+    TRANSCRIPT_FORMAT_VERSION, TRANSCRIPT_MAGIC,
+};
+
+// POLLS
+// ================================================================================================
+
+fn options() -> Vec<String> {
+    vec!["Yes".to_string(), "No".to_string(), "Abstain".to_string()]
+}
+
+#[test]
+fn poll_tallies_votes() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let mut poll = Poll::new(&access_set, TOPIC, options()).unwrap();
+
+    for (i, &option) in [0, 1, 0].iter().enumerate() {
+        let vote = poll
+            .make_vote(&access_set, &identities[i], option, &prover)
+            .unwrap();
+        poll.accept_vote(option, vote).unwrap();
+    }
+
+    let tally = poll.tally();
+    assert_eq!(vec![2, 1, 0], tally.counts);
+    assert_eq!(3, tally.total());
+}
+
+#[test]
+fn poll_rejects_invalid_and_duplicate_votes() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let mut poll = Poll::new(&access_set, TOPIC, options()).unwrap();
+
+    let vote = poll
+        .make_vote(&access_set, &identities[3], 0, &prover)
+        .unwrap();
+    assert_eq!(
+        SemaphoreError::InvalidOption(3),
+        poll.make_vote(&access_set, &identities[3], 3, &prover)
+            .unwrap_err()
+    );
+
+    // a vote cannot be moved to a different option, or to an option which does not exist
+    let result = poll.accept_vote(1, vote.clone());
+    assert!(matches!(result, Err(SemaphoreError::VerificationFailed(_))));
+    let result = poll.accept_vote(3, vote.clone());
+    assert_eq!(Err(SemaphoreError::InvalidOption(3)), result);
+
+    // a user can vote only once
+    poll.accept_vote(0, vote).unwrap();
+    let vote = poll
+        .make_vote(&access_set, &identities[3], 1, &prover)
+        .unwrap();
+    let result = poll.accept_vote(1, vote);
+    assert!(matches!(
+        result,
+        Err(SemaphoreError::DuplicateNullifier { .. })
+    ));
+
+    // a signal on a different topic is not a valid vote
+    let signal = access_set
+        .make_signal(&identities[4], "other topic", b"Yes", &prover)
+        .unwrap();
+    let result = poll.accept_vote(0, signal);
+    assert!(matches!(result, Err(SemaphoreError::VerificationFailed(_))));
+
+    assert_eq!(vec![1, 0, 0], poll.tally().counts);
+}
+
+#[test]
+fn poll_with_invalid_options_or_topic_is_rejected() {
+    let (_, access_set) = build_access_set(8);
+    let invalid = [
+        (
+            Vec::new(),
+            "invalid poll: a poll must have at least one option",
+        ),
+        (
+            vec!["Yes".to_string(), "Yes".to_string()],
+            "invalid poll: option 'Yes' is not unique",
+        ),
+    ];
+    for (options, expected) in invalid.iter() {
+        match Poll::new(&access_set, TOPIC, options.clone()) {
+            Err(err) => assert_eq!(*expected, err.to_string()),
+            Ok(_) => panic!("poll with options {:?} was created", options),
+        }
+    }
+
+    let topic = Topic::from(TOPIC).with_message_limit(2).unwrap();
+    assert!(matches!(
+        Poll::new(&access_set, topic, options()),
+        Err(SemaphoreError::InvalidPoll(_))
+    ));
+
+    // a transcript with invalid options cannot be verified
+    let mut transcript = Poll::new(&access_set, TOPIC, options())
+        .unwrap()
+        .transcript();
+    transcript.options.clear();
+    assert!(matches!(
+        transcript.verify(access_set.root(), access_set.depth(), Topic::from(TOPIC), &VerificationPolicy::default()),
+        Err(SemaphoreError::InvalidTranscript(err)) if matches!(*err, SemaphoreError::InvalidPoll(_))
+    ));
+}
+
+// TRANSCRIPTS
+// ================================================================================================
+
+#[test]
+fn transcript_can_be_verified_after_serialization() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let mut poll = Poll::new(&access_set, TOPIC, options()).unwrap();
+    for (i, &option) in [2, 1].iter().enumerate() {
+        let vote = poll
+            .make_vote(&access_set, &identities[i], option, &prover)
+            .unwrap();
+        poll.accept_vote(option, vote).unwrap();
+    }
+
+    let bytes = poll.transcript().to_bytes();
+    assert_eq!(TRANSCRIPT_MAGIC, bytes[..4]);
+    assert_eq!(TRANSCRIPT_FORMAT_VERSION, bytes[4]);
+    let transcript = Transcript::from_bytes(&bytes).unwrap();
+    assert_eq!(access_set.root(), transcript.root);
+    assert_eq!(options(), transcript.options);
+
+    let tally = transcript
+        .verify(
+            access_set.root(),
+            access_set.depth(),
+            Topic::from(TOPIC),
+            &VerificationPolicy::default(),
+        )
+        .unwrap();
+    assert_eq!(poll.tally(), tally);
+
+    // trailing and missing bytes are rejected
+    let mut extended = bytes.clone();
+    extended.push(0);
+    assert!(Transcript::from_bytes(&extended).is_err());
+    assert!(Transcript::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn malformed_transcript_lengths_are_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let mut poll = Poll::new(&access_set, TOPIC, options()).unwrap();
+    let vote = poll
+        .make_vote(&access_set, &identities[0], 1, &prover)
+        .unwrap();
+    poll.accept_vote(1, vote).unwrap();
+    let bytes = poll.transcript().to_bytes();
+
+    // the magic header and the format version are checked first
+    let mut tampered = bytes.clone();
+    tampered[0] ^= 1;
+    assert_eq!(
+        "invalid encoding: invalid transcript magic header",
+        Transcript::from_bytes(&tampered).unwrap_err().to_string()
+    );
+    let mut tampered = bytes.clone();
+    tampered[4] = TRANSCRIPT_FORMAT_VERSION + 1;
+    assert_eq!(
+        "invalid encoding: unsupported transcript format version 2; expected version 1",
+        Transcript::from_bytes(&tampered).unwrap_err().to_string()
+    );
+
+    // the depth follows the header and the 32-byte root
+    for &depth in [0u8, 33].iter() {
+        let mut tampered = bytes.clone();
+        tampered[37] = depth;
+        assert_eq!(
+            format!(
+                "invalid encoding: access set depth must be between 1 and 32, but was {}",
+                depth
+            ),
+            Transcript::from_bytes(&tampered).unwrap_err().to_string()
+        );
+    }
+
+    // the number of votes follows the header and the options
+    let num_ballots_offset = 73 + options().iter().map(|o| 4 + o.len()).sum::<usize>();
+    let mut tampered = bytes.clone();
+    tampered[num_ballots_offset..num_ballots_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(Transcript::from_bytes(&tampered)
+        .unwrap_err()
+        .to_string()
+        .contains("votes cannot fit into the remaining"));
+
+    // the proof length follows the option index and the nullifier of the vote
+    let proof_len_offset = num_ballots_offset + 4 + 2 + 32;
+    let mut tampered = bytes;
+    tampered[proof_len_offset..proof_len_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(Transcript::from_bytes(&tampered)
+        .unwrap_err()
+        .to_string()
+        .contains("exceeds the maximum of 1048576 bytes"));
+}
+
+#[test]
+fn tampered_transcript_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let mut poll = Poll::new(&access_set, TOPIC, options()).unwrap();
+    let vote = poll
+        .make_vote(&access_set, &identities[0], 0, &prover)
+        .unwrap();
+    poll.accept_vote(0, vote).unwrap();
+    let policy = VerificationPolicy::default();
+
+    // moving a vote to a different option
+    let mut transcript = poll.transcript();
+    transcript.ballots[0].option = 1;
+    assert!(transcript
+        .verify(
+            access_set.root(),
+            access_set.depth(),
+            Topic::from(TOPIC),
+            &policy
+        )
+        .is_err());
+
+    // counting the same vote twice
+    let mut transcript = poll.transcript();
+    transcript.ballots.push(transcript.ballots[0].clone());
+    assert!(matches!(
+        transcript.verify(
+            access_set.root(),
+            access_set.depth(),
+            Topic::from(TOPIC),
+            &policy
+        ),
+        Err(SemaphoreError::DuplicateNullifier { .. })
+    ));
+
+    // renaming an option
+    let mut transcript = poll.transcript();
+    transcript.options[0] = "No way".to_string();
+    assert!(transcript
+        .verify(
+            access_set.root(),
+            access_set.depth(),
+            Topic::from(TOPIC),
+            &policy
+        )
+        .is_err());
+}
+
+#[test]
+fn transcript_of_slashing_poll_can_be_verified() {
+    let (identities, access_set) = build_access_set(8);
+    let topic = Topic::from(TOPIC).with_slashing();
+    let mut poll = Poll::new(&access_set, topic, options()).unwrap();
+    let vote = poll
+        .make_vote(&access_set, &identities[5], 1, &SemaphoreProver::default())
+        .unwrap();
+    assert!(vote.key_share.is_some());
+    poll.accept_vote(1, vote).unwrap();
+
+    let transcript = Transcript::from_bytes(&poll.transcript().to_bytes()).unwrap();
+    assert_eq!(topic, transcript.topic);
+    let tally = transcript
+        .verify(
+            access_set.root(),
+            access_set.depth(),
+            topic,
+            &VerificationPolicy::default(),
+        )
+        .unwrap();
+    assert_eq!(vec![0, 1, 0], tally.counts);
+}
+
+#[test]
+fn transcript_of_different_poll_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let policy = VerificationPolicy::default();
+
+    // a poll among keys of a different access set of the same depth
+    let (other_identities, other_set) = build_access_set(7);
+    assert_eq!(access_set.depth(), other_set.depth());
+    let mut poll = Poll::new(&other_set, TOPIC, options()).unwrap();
+    let vote = poll
+        .make_vote(
+            &other_set,
+            &other_identities[0],
+            0,
+            &SemaphoreProver::default(),
+        )
+        .unwrap();
+    poll.accept_vote(0, vote).unwrap();
+    let transcript = poll.transcript();
+    assert!(transcript
+        .verify(
+            other_set.root(),
+            other_set.depth(),
+            Topic::from(TOPIC),
+            &policy
+        )
+        .is_ok());
+    assert!(matches!(
+        transcript.verify(access_set.root(), access_set.depth(), Topic::from(TOPIC), &policy),
+        Err(SemaphoreError::InvalidTranscript(err))
+            if matches!(*err, SemaphoreError::RootMismatch { .. })
+    ));
+
+    // a poll among the same keys, but on a different topic or for a different depth
+    let mut poll = Poll::new(&access_set, TOPIC, options()).unwrap();
+    let vote = poll
+        .make_vote(&access_set, &identities[0], 0, &SemaphoreProver::default())
+        .unwrap();
+    poll.accept_vote(0, vote).unwrap();
+    let transcript = poll.transcript();
+    assert!(matches!(
+        transcript.verify(access_set.root(), access_set.depth(), Topic::from("other topic"), &policy),
+        Err(SemaphoreError::InvalidTranscript(err))
+            if matches!(*err, SemaphoreError::InvalidPoll(_))
+    ));
+    assert!(matches!(
+        transcript.verify(access_set.root(), 4, Topic::from(TOPIC), &policy),
+        Err(SemaphoreError::InvalidTranscript(err))
+            if *err == SemaphoreError::DepthMismatch { expected: 4, actual: 3 }
+    ));
+}
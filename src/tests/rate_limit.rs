@@ -0,0 +1,142 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    debug_trace, Hasher, InMemoryNullifierStore, Prover, Rescue, SemaphoreAir, SemaphoreError,
+    SemaphoreProver, Topic, Trace, MAX_MESSAGE_LIMIT,
+};
+use winterfell::{Air, TraceInfo};
+
+// RATE-LIMITED SIGNALS
+// ================================================================================================
+
+#[test]
+fn user_can_signal_up_to_message_limit() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let topic = Topic::from(TOPIC).with_message_limit(3).unwrap();
+    let mut store = InMemoryNullifierStore::new();
+
+    // every message index yields a distinct nullifier, and index 0 yields the plain nullifier
+    let mut nullifiers = Vec::new();
+    for message_index in 0..3 {
+        let signal = access_set
+            .make_rate_limited_signal(&identities[2], topic, MESSAGE, message_index, &prover)
+            .unwrap();
+        assert!(!nullifiers.contains(&signal.nullifier));
+        nullifiers.push(signal.nullifier);
+        assert!(access_set
+            .accept_signal(topic, MESSAGE, signal, &mut store)
+            .is_ok());
+    }
+    assert_eq!(identities[2].get_nullifier(topic.digest()), nullifiers[0]);
+
+    // reusing a message index yields a duplicate nullifier even for a different message
+    let signal = access_set
+        .make_rate_limited_signal(&identities[2], topic, b"No", 1, &prover)
+        .unwrap();
+    let result = access_set.accept_signal(topic, b"No", signal, &mut store);
+    assert!(matches!(
+        result,
+        Err(SemaphoreError::DuplicateNullifier { nullifier, .. }) if nullifier == nullifiers[1]
+    ));
+}
+
+#[test]
+fn signal_is_bound_to_message_limit() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let topic = Topic::from(TOPIC).with_message_limit(3).unwrap();
+
+    let signal = access_set
+        .make_rate_limited_signal(&identities[0], topic, MESSAGE, 2, &prover)
+        .unwrap();
+    assert!(access_set
+        .verify_signal(topic, MESSAGE, signal.clone())
+        .is_ok());
+    for &message_limit in [1, 2, 4].iter() {
+        let other_topic = Topic::from(TOPIC)
+            .with_message_limit(message_limit)
+            .unwrap();
+        assert!(access_set
+            .verify_signal(other_topic, MESSAGE, signal.clone())
+            .is_err());
+    }
+}
+
+#[test]
+fn invalid_message_limits_are_rejected() {
+    for &message_limit in [0, MAX_MESSAGE_LIMIT + 1].iter() {
+        let err = Topic::from(TOPIC)
+            .with_message_limit(message_limit)
+            .unwrap_err();
+        assert_eq!(SemaphoreError::InvalidMessageLimit(message_limit), err);
+    }
+}
+
+#[test]
+fn signals_can_be_made_for_extreme_message_indexes() {
+    // the prover checks constraint degrees in debug builds; the largest index results in range
+    // check columns with all bits set
+    let (identities, access_set) = build_access_set(2);
+    let prover = SemaphoreProver::default();
+    let topic = Topic::from(TOPIC)
+        .with_message_limit(MAX_MESSAGE_LIMIT)
+        .unwrap();
+    for &message_index in [0, MAX_MESSAGE_LIMIT - 1].iter() {
+        let signal = access_set
+            .make_rate_limited_signal(&identities[1], topic, MESSAGE, message_index, &prover)
+            .unwrap();
+        assert!(access_set.verify_signal(topic, MESSAGE, signal).is_ok());
+    }
+}
+
+#[test]
+fn message_index_must_be_below_limit() {
+    let (identities, access_set) = build_access_set(8);
+    let topic = Topic::from(TOPIC).with_message_limit(3).unwrap();
+    let err = access_set
+        .make_rate_limited_signal(
+            &identities[0],
+            topic,
+            MESSAGE,
+            3,
+            &SemaphoreProver::default(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        SemaphoreError::InvalidMessageIndex { index: 3, limit: 3 },
+        err
+    );
+}
+
+#[test]
+fn trace_with_message_index_out_of_range_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let topic = Topic::from(TOPIC).with_message_limit(3).unwrap();
+    let message = Rescue::hash(MESSAGE);
+    let key_path = access_set.get_key_path(4).unwrap();
+
+    for &message_index in [3, MAX_MESSAGE_LIMIT, u32::MAX].iter() {
+        let trace = prover
+            .build_trace(&identities[4], 4, topic, message_index, message, &key_path)
+            .unwrap();
+
+        // the trace must be checked against the message limit of the topic rather than the
+        // limit read from the trace
+        let mut pub_inputs = prover.get_pub_inputs(&trace);
+        pub_inputs.message_limit = topic.message_limit();
+        let air = SemaphoreAir::new(
+            TraceInfo::new(trace.width(), trace.length()),
+            pub_inputs,
+            prover.options().clone(),
+        );
+        let failures = debug_trace(&air, &trace);
+        assert!(
+            failures
+                .iter()
+                .any(|failure| failure.name.starts_with("message index range")),
+            "index {} passed the range checks",
+            message_index
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    Group, GroupRegistry, GroupSignal, InMemoryNullifierStore, IncrementalAccessSet,
+    SemaphoreError, SemaphoreProver,
+};
+
+// GROUP LOOKUP
+// ================================================================================================
+
+#[test]
+fn signal_is_verified_against_its_group() {
+    let prover = SemaphoreProver::default();
+    let (identities, access_set) = build_access_set(5);
+    let (other_identities, other_set) = build_access_set(6);
+
+    let mut registry = GroupRegistry::new();
+    assert!(registry.insert(1, Group::from(&access_set)).is_none());
+    assert!(registry.insert(2, Group::from(&other_set)).is_none());
+    assert_eq!(2, registry.num_groups());
+    assert_eq!(other_set.root(), registry.get(2).unwrap().root());
+
+    let signal = access_set
+        .make_signal(&identities[0], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(registry
+        .verify_signal(TOPIC, MESSAGE, GroupSignal::new(1, signal.clone()))
+        .is_ok());
+
+    // the same signal is not valid for a group with a different access set
+    assert_eq!(
+        SemaphoreError::UnknownRoot(access_set.root()),
+        registry
+            .verify_signal(TOPIC, MESSAGE, GroupSignal::new(2, signal.clone()))
+            .unwrap_err()
+    );
+
+    let err = registry
+        .verify_signal(TOPIC, MESSAGE, GroupSignal::new(3, signal))
+        .unwrap_err();
+    assert_eq!(SemaphoreError::UnknownGroup(3), err);
+
+    let signal = other_set
+        .make_signal(&other_identities[5], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(registry
+        .verify_signal(TOPIC, MESSAGE, GroupSignal::new(2, signal))
+        .is_ok());
+}
+
+#[test]
+fn duplicate_group_signal_is_rejected() {
+    let prover = SemaphoreProver::default();
+    let (identities, access_set) = build_access_set(2);
+    let mut store = InMemoryNullifierStore::new();
+
+    let mut registry = GroupRegistry::new();
+    registry.insert(7, Group::from(&access_set));
+
+    let signal = GroupSignal::new(
+        7,
+        access_set
+            .make_signal(&identities[1], TOPIC, MESSAGE, &prover)
+            .unwrap(),
+    );
+    assert!(registry
+        .accept_signal(TOPIC, MESSAGE, signal.clone(), &mut store)
+        .is_ok());
+    assert!(matches!(
+        registry.accept_signal(TOPIC, MESSAGE, signal, &mut store),
+        Err(SemaphoreError::DuplicateNullifier { .. })
+    ));
+}
+
+// ROOT HISTORY
+// ================================================================================================
+
+#[test]
+fn group_root_history_follows_updates() {
+    let prover = SemaphoreProver::default();
+    let (identities, _) = build_access_set(4);
+
+    let mut incremental_set = IncrementalAccessSet::new(2, 2).unwrap();
+    incremental_set.append(identities[0].pub_key()).unwrap();
+
+    let mut registry = GroupRegistry::new();
+    registry.insert(1, Group::from(&incremental_set));
+    assert_eq!(2, registry.get(1).unwrap().root_history().count());
+    let signal = incremental_set
+        .make_signal(&identities[0], TOPIC, MESSAGE, &prover)
+        .unwrap();
+
+    // after one update, the signal is made against the previous root of the group
+    incremental_set.append(identities[1].pub_key()).unwrap();
+    registry.update_root(1, incremental_set.root()).unwrap();
+    assert_eq!(incremental_set.root(), registry.get(1).unwrap().root());
+    assert!(registry
+        .verify_signal(TOPIC, MESSAGE, GroupSignal::new(1, signal.clone()))
+        .is_ok());
+
+    // after another update, the root of the signal is evicted from the history of the group
+    incremental_set.append(identities[2].pub_key()).unwrap();
+    registry.update_root(1, incremental_set.root()).unwrap();
+    assert!(registry
+        .verify_signal(TOPIC, MESSAGE, GroupSignal::new(1, signal))
+        .is_err());
+
+    assert_eq!(
+        SemaphoreError::UnknownGroup(2),
+        registry.update_root(2, incremental_set.root()).unwrap_err()
+    );
+}
+
+#[test]
+fn group_root_history_is_revoked_after_removal() {
+    let prover = SemaphoreProver::default();
+    let (identities, _) = build_access_set(4);
+
+    let mut incremental_set = IncrementalAccessSet::new(2, 4).unwrap();
+    incremental_set.append(identities[0].pub_key()).unwrap();
+    incremental_set.append(identities[1].pub_key()).unwrap();
+    let mut registry = GroupRegistry::new();
+    registry.insert(1, Group::from(&incremental_set));
+    let signal = incremental_set
+        .make_signal(&identities[0], TOPIC, MESSAGE, &prover)
+        .unwrap();
+
+    // after the key of the signal is removed, its root is no longer accepted
+    incremental_set.remove(0).unwrap();
+    registry
+        .revoke_root_history(1, incremental_set.root())
+        .unwrap();
+    assert_eq!(1, registry.get(1).unwrap().root_history().count());
+    assert_eq!(incremental_set.root(), registry.get(1).unwrap().root());
+    assert_eq!(
+        SemaphoreError::UnknownRoot(signal.root),
+        registry
+            .verify_signal(TOPIC, MESSAGE, GroupSignal::new(1, signal))
+            .unwrap_err()
+    );
+
+    assert_eq!(
+        SemaphoreError::UnknownGroup(2),
+        registry
+            .revoke_root_history(2, incremental_set.root())
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn group_with_invalid_parameters_is_rejected() {
+    let (_, access_set) = build_access_set(8);
+    for &depth in [0, 33].iter() {
+        assert_eq!(
+            SemaphoreError::InvalidDepth(depth),
+            Group::new(access_set.root(), depth, 1).unwrap_err()
+        );
+    }
+    assert_eq!(
+        SemaphoreError::InvalidHistorySize(0),
+        Group::new(access_set.root(), 3, 0).unwrap_err()
+    );
+}
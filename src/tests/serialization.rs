@@ -0,0 +1,221 @@
+use super::{build_access_set, temp_path, MESSAGE, TOPIC};
+use crate::{
+    AccessSet, AccessSetDescriptor, AccessSetFormat, SemaphoreError, SemaphoreProver, Signal,
+    Topic, ACCESS_SET_FORMAT_VERSION, ACCESS_SET_MAGIC, MAX_SIGNAL_PROOF_SIZE,
+    SIGNAL_FORMAT_VERSION, SIGNAL_MAGIC,
+};
+use std::{fs, io};
+use winter_utils::DeserializationError;
+
+// SIGNAL SERIALIZATION
+// ================================================================================================
+
+#[test]
+fn signal_can_be_verified_after_serialization() {
+    let prover = SemaphoreProver::default();
+    let (identities, access_set) = build_access_set(8);
+
+    let signal = access_set
+        .make_signal(&identities[3], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    let bytes = signal.to_bytes();
+    assert_eq!(SIGNAL_MAGIC, bytes[..4]);
+    assert_eq!(SIGNAL_FORMAT_VERSION, bytes[4]);
+
+    let decoded = Signal::from_bytes(&bytes).unwrap();
+    assert_eq!(signal.nullifier, decoded.nullifier);
+    assert_eq!(None, decoded.key_share);
+    assert!(access_set.verify_signal(TOPIC, MESSAGE, decoded).is_ok());
+
+    // key shares of slashing signals are serialized as well
+    let topic = Topic::from(TOPIC).with_slashing();
+    let signal = access_set
+        .make_signal(&identities[3], topic, MESSAGE, &prover)
+        .unwrap();
+    let decoded = Signal::from_bytes(&signal.to_bytes()).unwrap();
+    assert_eq!(signal.key_share, decoded.key_share);
+    assert!(access_set.verify_signal(topic, MESSAGE, decoded).is_ok());
+}
+
+#[test]
+fn malformed_signal_bytes_are_rejected() {
+    let (identities, access_set) = build_access_set(2);
+    let signal = access_set
+        .make_signal(&identities[0], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+    let bytes = signal.to_bytes();
+
+    // truncated input
+    for &len in [0, 3, 5, 6, 38, 70, 71, bytes.len() - 1].iter() {
+        assert_eq!(
+            Err(SemaphoreError::Deserialization(
+                DeserializationError::UnexpectedEOF
+            )),
+            Signal::from_bytes(&bytes[..len]).map(|_| ())
+        );
+    }
+
+    // trailing bytes
+    let mut oversized = bytes.clone();
+    oversized.push(0);
+    assert_eq!(
+        Err(SemaphoreError::Deserialization(
+            DeserializationError::UnconsumedBytes
+        )),
+        Signal::from_bytes(&oversized).map(|_| ())
+    );
+
+    // proof length above the maximum
+    let mut oversized = bytes[..71].to_vec();
+    oversized.extend_from_slice(&(MAX_SIGNAL_PROOF_SIZE as u32 + 1).to_le_bytes());
+    assert_invalid_value(
+        &oversized,
+        "signal proof size of 1048577 bytes exceeds the maximum",
+    );
+
+    // unknown version
+    let mut unknown_version = bytes.clone();
+    unknown_version[4] = SIGNAL_FORMAT_VERSION + 1;
+    assert_invalid_value(&unknown_version, "unsupported signal format version 2");
+
+    // invalid magic header
+    let mut invalid_magic = bytes.clone();
+    invalid_magic[0] ^= 1;
+    assert_invalid_value(&invalid_magic, "invalid signal magic header");
+
+    // invalid depth
+    let mut invalid_depth = bytes.clone();
+    invalid_depth[5] = 0;
+    assert_invalid_value(
+        &invalid_depth,
+        "access set depth must be between 1 and 32, but was 0",
+    );
+
+    // invalid key share flag
+    let mut invalid_flag = bytes;
+    invalid_flag[70] = 2;
+    assert_invalid_value(&invalid_flag, "invalid key share flag 2");
+}
+
+// ACCESS SET FILES
+// ================================================================================================
+
+#[test]
+fn access_set_can_be_loaded_after_save() {
+    let prover = SemaphoreProver::default();
+    let (identities, mut access_set) = build_access_set(5);
+    access_set.remove(1).unwrap();
+
+    for &format in [AccessSetFormat::Binary, AccessSetFormat::Text].iter() {
+        let path = temp_path(&format!("access_set_can_be_loaded_after_save_{:?}", format));
+        access_set.save(&path, format).unwrap();
+        let loaded_set = AccessSet::load(&path, format).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // removed keys keep their slots, and thus, all key paths are the same
+        assert_eq!(access_set.descriptor(), loaded_set.descriptor());
+        assert_eq!(5, loaded_set.num_keys());
+        assert_eq!(
+            access_set.get_key_path(4).unwrap(),
+            loaded_set.get_key_path(4).unwrap()
+        );
+    }
+
+    let bytes = access_set.descriptor().to_bytes();
+    assert_eq!(ACCESS_SET_MAGIC, bytes[..4]);
+    assert_eq!(ACCESS_SET_FORMAT_VERSION, bytes[4]);
+    let loaded_set =
+        AccessSet::from_descriptor(&AccessSetDescriptor::from_bytes(&bytes).unwrap()).unwrap();
+    let signal = loaded_set
+        .make_signal(&identities[3], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_ok());
+}
+
+#[test]
+fn tampered_access_set_file_is_rejected() {
+    let (_, access_set) = build_access_set(5);
+    let (identities, _) = build_access_set(9);
+    let path = temp_path("tampered_access_set_file_is_rejected");
+
+    // a leaf replaced with a different public key does not hash into the recorded root
+    let mut descriptor = access_set.descriptor();
+    descriptor.leaves[2] = identities[7].pub_key().0;
+    fs::write(&path, descriptor.to_bytes()).unwrap();
+    let err = AccessSet::load(&path, AccessSetFormat::Binary)
+        .err()
+        .unwrap();
+    assert_eq!(
+        SemaphoreError::RootMismatch {
+            expected: access_set.root(),
+            actual: AccessSet::from_leaves(descriptor.leaves).root()
+        },
+        err
+    );
+
+    // appending leaves changes the depth of the tree
+    let mut descriptor = access_set.descriptor();
+    descriptor
+        .leaves
+        .extend(identities[5..9].iter().map(|i| i.pub_key().0));
+    fs::write(&path, descriptor.to_json()).unwrap();
+    let err = AccessSet::load(&path, AccessSetFormat::Text).err().unwrap();
+    assert_eq!(
+        SemaphoreError::DepthMismatch {
+            expected: 3,
+            actual: 4
+        },
+        err
+    );
+
+    // the depth must be in the supported range
+    let mut descriptor = access_set.descriptor();
+    descriptor.depth = 33;
+    fs::write(&path, descriptor.to_bytes()).unwrap();
+    let err = AccessSet::load(&path, AccessSetFormat::Binary)
+        .err()
+        .unwrap();
+    assert_eq!(SemaphoreError::InvalidDepth(33), err);
+
+    // a truncated file cannot be read
+    let bytes = access_set.descriptor().to_bytes();
+    fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+    let err = AccessSet::load(&path, AccessSetFormat::Binary)
+        .err()
+        .unwrap();
+    assert_eq!(
+        SemaphoreError::Deserialization(DeserializationError::UnexpectedEOF),
+        err
+    );
+
+    // a binary file cannot be loaded as text
+    fs::write(&path, &bytes).unwrap();
+    let err = AccessSet::load(&path, AccessSetFormat::Text).err().unwrap();
+    assert!(
+        matches!(err, SemaphoreError::Deserialization(_)),
+        "unexpected error: {}",
+        err
+    );
+
+    // a missing file cannot be read
+    fs::remove_file(&path).unwrap();
+    let err = AccessSet::load(&path, AccessSetFormat::Binary)
+        .err()
+        .unwrap();
+    assert_eq!(
+        SemaphoreError::Io(io::Error::from(io::ErrorKind::NotFound)),
+        err
+    );
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn assert_invalid_value(bytes: &[u8], expected: &str) {
+    match Signal::from_bytes(bytes) {
+        Err(SemaphoreError::Deserialization(DeserializationError::InvalidValue(err))) => {
+            assert!(err.starts_with(expected), "unexpected error: {}", err)
+        }
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+}
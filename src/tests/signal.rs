@@ -0,0 +1,224 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    AccessSet, InMemoryNullifierStore, PubKey, SecurityPreset, SemaphoreError, SemaphoreProver,
+    SignalVerifier, VerificationPolicy, MAX_TREE_DEPTH,
+};
+
+// SIGNAL VERIFICATION
+// ================================================================================================
+
+#[test]
+fn signal_for_access_set_of_different_depth_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let signal = access_set
+        .make_signal(&identities[5], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+    assert_eq!(access_set.trace_length(), signal.proof.trace_length());
+
+    // access sets with 2 and 128 keys have depths 1 and 7 respectively
+    for &num_keys in [2, 128].iter() {
+        let (_, other_set) = build_access_set(num_keys);
+        assert_eq!(
+            SemaphoreError::SignalDepthMismatch {
+                expected: other_set.depth(),
+                actual: 3
+            },
+            other_set
+                .verify_signal(TOPIC, MESSAGE, signal.clone())
+                .unwrap_err()
+        );
+
+        // a signal which claims the depth and the root of the other set is rejected based on
+        // its trace length
+        let mut forged = signal.clone();
+        forged.root = other_set.root();
+        forged.depth = other_set.depth();
+        let err = other_set.verify_signal(TOPIC, MESSAGE, forged).unwrap_err();
+        assert!(
+            matches!(err, SemaphoreError::InvalidTraceLength { .. }),
+            "unexpected error: {}",
+            err
+        );
+    }
+}
+
+#[test]
+fn signal_for_access_set_of_same_depth_fails_proof_verification() {
+    let (identities, access_set) = build_access_set(8);
+    let signal = access_set
+        .make_signal(&identities[5], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+
+    // build an access set of the same depth from a different set of keys
+    let (other_keys, _) = build_access_set(128);
+    let other_set = AccessSet::new(other_keys[8..16].iter().map(PubKey::new).collect()).unwrap();
+    assert_eq!(access_set.depth(), other_set.depth());
+    assert_eq!(
+        SemaphoreError::UnknownRoot(access_set.root()),
+        other_set
+            .verify_signal(TOPIC, MESSAGE, signal.clone())
+            .unwrap_err()
+    );
+
+    // a signal which claims the root of the other set fails proof verification
+    let mut forged = signal;
+    forged.root = other_set.root();
+    let err = other_set.verify_signal(TOPIC, MESSAGE, forged).unwrap_err();
+    assert!(
+        matches!(err, SemaphoreError::VerificationFailed(_)),
+        "unexpected error: {}",
+        err
+    );
+}
+
+// MESSAGE BINDING
+// ================================================================================================
+
+#[test]
+fn signal_is_bound_to_its_message() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let signal = access_set
+        .make_signal(&identities[5], TOPIC, b"Yes", &prover)
+        .unwrap();
+    assert!(access_set
+        .verify_signal(TOPIC, b"Yes", signal.clone())
+        .is_ok());
+
+    // a relayer cannot attach the signal to a different message
+    let err = access_set
+        .verify_signal(TOPIC, b"No", signal.clone())
+        .unwrap_err();
+    assert!(
+        matches!(err, SemaphoreError::VerificationFailed(_)),
+        "unexpected error: {}",
+        err
+    );
+
+    // the nullifier does not depend on the message
+    let other_signal = access_set
+        .make_signal(&identities[5], TOPIC, b"No", &prover)
+        .unwrap();
+    assert_eq!(signal.nullifier, other_signal.nullifier);
+    assert!(access_set.verify_signal(TOPIC, b"No", other_signal).is_ok());
+}
+
+// PROVER PRESETS
+// ================================================================================================
+
+#[test]
+fn signals_made_with_presets_meet_target_security() {
+    let expected = [
+        (SecurityPreset::Fast96, 96),
+        (SecurityPreset::Standard128, 128),
+        (SecurityPreset::Paranoid, 128),
+    ];
+
+    // access sets with 2 and 128 keys result in the shortest and longest traces used in tests
+    for &num_keys in [2, 128].iter() {
+        let (identities, access_set) = build_access_set(num_keys);
+        for &(preset, security_level) in expected.iter() {
+            let prover = SemaphoreProver::with_preset(preset);
+            let signal = access_set
+                .make_signal(&identities[1], TOPIC, MESSAGE, &prover)
+                .unwrap();
+            assert_eq!(&preset.options(), signal.proof.options());
+            assert_eq!(security_level, signal.proof.security_level(true));
+            assert!(access_set.verify_signal(TOPIC, MESSAGE, signal).is_ok());
+        }
+    }
+}
+
+// STANDALONE VERIFIER
+// ================================================================================================
+
+#[test]
+fn signal_is_verified_with_root_and_depth_only() {
+    let (identities, access_set) = build_access_set(8);
+    let signal = access_set
+        .make_signal(&identities[5], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+
+    // the verifier does not need the access set, only its root and depth
+    let (root, depth) = (access_set.root(), access_set.depth());
+    drop(access_set);
+    let verifier = SignalVerifier::new(root, depth, VerificationPolicy::default()).unwrap();
+    assert!(verifier
+        .verify_signal(TOPIC, MESSAGE, signal.clone())
+        .is_ok());
+    assert!(verifier
+        .verify_signal(TOPIC, b"No", signal.clone())
+        .is_err());
+
+    // the verifier also records nullifiers of accepted signals
+    let mut store = InMemoryNullifierStore::new();
+    assert!(verifier
+        .accept_signal(TOPIC, MESSAGE, signal.clone(), &mut store)
+        .is_ok());
+    assert!(verifier
+        .accept_signal(TOPIC, MESSAGE, signal, &mut store)
+        .is_err());
+}
+
+#[test]
+fn standalone_verifier_checks_depth() {
+    let (identities, access_set) = build_access_set(8);
+    let signal = access_set
+        .make_signal(&identities[5], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+    assert_eq!(3, signal.depth());
+
+    // traces for depths 3 and 4 are padded to the same length, and thus, the depth must be
+    // checked explicitly
+    for &depth in [4, 7].iter() {
+        let verifier =
+            SignalVerifier::new(access_set.root(), depth, VerificationPolicy::default()).unwrap();
+        assert_eq!(
+            SemaphoreError::SignalDepthMismatch {
+                expected: depth,
+                actual: 3
+            },
+            verifier
+                .verify_signal(TOPIC, MESSAGE, signal.clone())
+                .unwrap_err()
+        );
+    }
+
+    // a signal which claims a wrong depth is rejected as well
+    let verifier =
+        SignalVerifier::new(access_set.root(), 4, VerificationPolicy::default()).unwrap();
+    let mut forged = signal;
+    forged.depth = 4;
+    let err = verifier.verify_signal(TOPIC, MESSAGE, forged).unwrap_err();
+    assert!(
+        matches!(err, SemaphoreError::VerificationFailed(_)),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn verifier_with_invalid_depth_is_rejected() {
+    let (_, access_set) = build_access_set(8);
+    for &depth in [0, MAX_TREE_DEPTH + 1].iter() {
+        let err = SignalVerifier::new(access_set.root(), depth, VerificationPolicy::default())
+            .unwrap_err();
+        assert_eq!(SemaphoreError::InvalidDepth(depth), err);
+        assert_eq!(
+            format!(
+                "access set depth must be between 1 and {}, but was {}",
+                MAX_TREE_DEPTH, depth
+            ),
+            err.to_string()
+        );
+        assert_eq!(
+            SemaphoreError::InvalidDepth(depth),
+            SignalVerifier::with_roots(
+                vec![access_set.root()],
+                depth,
+                VerificationPolicy::default()
+            )
+            .unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    hash_bytes, recover_identity_from_double_signal, Digest, Felt, FieldElement, Hasher,
+    InMemoryNullifierStore, Prover, Rescue, SemaphoreAir, SemaphoreError, SemaphoreProver,
+    SlashingEvidence, Topic,
+};
+
+// KEY SHARES
+// ================================================================================================
+
+#[test]
+fn signal_reveals_key_share_only_on_slashing_topic() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let topic = Topic::from(TOPIC).with_slashing();
+
+    let signal = access_set
+        .make_signal(&identities[6], topic, MESSAGE, &prover)
+        .unwrap();
+    assert!(signal.key_share.is_some());
+    assert!(access_set
+        .verify_signal(topic, MESSAGE, signal.clone())
+        .is_ok());
+    let err = access_set
+        .verify_signal(TOPIC, MESSAGE, signal.clone())
+        .unwrap_err();
+    assert_eq!(SemaphoreError::UnexpectedKeyShare, err);
+
+    // both the nullifier secret and the trapdoor parts of the key share are bound to the proof
+    for &i in [2, 6].iter() {
+        let mut tampered = signal.clone();
+        tampered.key_share.as_mut().unwrap()[i] += Felt::ONE;
+        assert!(access_set.verify_signal(topic, MESSAGE, tampered).is_err());
+    }
+
+    let signal = access_set
+        .make_signal(&identities[6], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(signal.key_share.is_none());
+    let err = access_set
+        .verify_signal(topic, MESSAGE, signal)
+        .unwrap_err();
+    assert_eq!(SemaphoreError::MissingKeyShare, err);
+}
+
+// KEY RECOVERY
+// ================================================================================================
+
+#[test]
+fn double_signal_reveals_identity() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let topic = Topic::from(TOPIC).with_slashing();
+    let mut store = InMemoryNullifierStore::new();
+
+    let first = access_set
+        .make_signal(&identities[5], topic, b"Yes", &prover)
+        .unwrap();
+    assert!(access_set
+        .accept_signal(topic, b"Yes", first.clone(), &mut store)
+        .is_ok());
+    let second = access_set
+        .make_signal(&identities[5], topic, b"No", &prover)
+        .unwrap();
+    assert!(matches!(
+        access_set.accept_signal(topic, b"No", second.clone(), &mut store),
+        Err(SemaphoreError::DuplicateNullifier { .. })
+    ));
+
+    let identity =
+        recover_identity_from_double_signal(topic, b"Yes", &first, b"No", &second).unwrap();
+    assert_eq!(identities[5], identity);
+
+    // the evidence can be exported and checked by anyone
+    let evidence = SlashingEvidence::new(topic, b"Yes", &first, b"No", &second).unwrap();
+    let evidence = SlashingEvidence::from_bytes(&evidence.to_bytes()).unwrap();
+    assert_eq!(topic, evidence.topic);
+    assert_eq!(identities[5], evidence.recover_identity().unwrap());
+
+    // repeating the same message does not reveal the identity
+    let err =
+        recover_identity_from_double_signal(topic, b"Yes", &first, b"Yes", &first).unwrap_err();
+    assert_eq!(
+        SemaphoreError::InvalidEvidence("key shares have the same x coordinate".to_string()),
+        err
+    );
+}
+
+#[test]
+fn messages_with_same_first_hash_element_reveal_identity() {
+    let (identities, access_set) = build_access_set(8);
+    let key_idx = 4;
+    let key_path = access_set.get_key_path(key_idx).unwrap();
+    let topic = Topic::from(TOPIC).with_slashing();
+    let prover = SemaphoreProver::default().with_slashing(true);
+
+    // hashes which differ only in their last element must still yield different x coordinates,
+    // and thus, key shares from which the identity can be recovered; such hashes cannot be found
+    // without breaking the hash function, and thus, the traces are built directly from them
+    let first: [Felt; 4] = Rescue::hash(MESSAGE).into();
+    let mut second = first;
+    second[3] += Felt::ONE;
+
+    let mut nullifier = Digest::default();
+    let mut key_shares = [(Digest::default(), [Felt::ZERO; 8]); 2];
+    for (key_share, &message) in key_shares.iter_mut().zip([first, second].iter()) {
+        let message = Digest::new(message);
+        let trace = prover
+            .build_trace(&identities[key_idx], key_idx, topic, 0, message, &key_path)
+            .unwrap();
+        let pub_inputs = prover.get_pub_inputs(&trace);
+        nullifier = pub_inputs.nullifier;
+        *key_share = (message, pub_inputs.key_share.unwrap());
+
+        let proof = prover.prove(trace).unwrap();
+        assert!(winterfell::verify::<SemaphoreAir>(proof, pub_inputs).is_ok());
+    }
+    assert_ne!(key_shares[0].1, key_shares[1].1);
+
+    let evidence = SlashingEvidence {
+        topic,
+        nullifier,
+        key_shares,
+    };
+    assert_eq!(identities[key_idx], evidence.recover_identity().unwrap());
+}
+
+#[test]
+fn messages_with_same_x_coordinate_do_not_reveal_identity() {
+    let (identities, access_set) = build_access_set(8);
+    let key_idx = 4;
+    let key_path = access_set.get_key_path(key_idx).unwrap();
+    let topic = Topic::from(TOPIC).with_slashing();
+    let prover = SemaphoreProver::default().with_slashing(true);
+
+    // the x coordinate is m0 + 7 * m1 + 49 * m2 + 343 * m3, and thus, adding 7 to the first
+    // element and subtracting 1 from the second one does not change it; such hashes can be found
+    // with a birthday search, and here, the traces are built directly from them
+    let first: [Felt; 4] = Rescue::hash(MESSAGE).into();
+    let mut second = first;
+    second[0] += Felt::new(7);
+    second[1] -= Felt::ONE;
+
+    let mut nullifier = Digest::default();
+    let mut key_shares = [(Digest::default(), [Felt::ZERO; 8]); 2];
+    for (key_share, &message) in key_shares.iter_mut().zip([first, second].iter()) {
+        let message = Digest::new(message);
+        let trace = prover
+            .build_trace(&identities[key_idx], key_idx, topic, 0, message, &key_path)
+            .unwrap();
+        let pub_inputs = prover.get_pub_inputs(&trace);
+        nullifier = pub_inputs.nullifier;
+        *key_share = (message, pub_inputs.key_share.unwrap());
+    }
+
+    // both signals reveal the same key share, which is rejected as evidence
+    assert_ne!(key_shares[0].0, key_shares[1].0);
+    assert_eq!(key_shares[0].1, key_shares[1].1);
+    let evidence = SlashingEvidence {
+        topic,
+        nullifier,
+        key_shares,
+    };
+    assert_eq!(
+        SemaphoreError::InvalidEvidence("key shares have the same x coordinate".to_string()),
+        evidence.recover_identity().unwrap_err()
+    );
+}
+
+#[test]
+fn single_signal_does_not_reveal_identity() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let topic = Topic::from(TOPIC).with_slashing();
+
+    let signal = access_set
+        .make_signal(&identities[3], topic, b"Yes", &prover)
+        .unwrap();
+    let key_share = signal.key_share.unwrap();
+    assert_ne!(identities[3].elements(), key_share);
+
+    // a signal reveals a single point on every share polynomial; interpolating this point with
+    // itself placed at another x coordinate does not yield the identity
+    let evidence = SlashingEvidence {
+        topic,
+        nullifier: signal.nullifier,
+        key_shares: [
+            (hash_bytes(b"Yes"), key_share),
+            (hash_bytes(b"No"), key_share),
+        ],
+    };
+    assert_eq!(
+        SemaphoreError::InvalidEvidence(
+            "recovered identity does not match the nullifier".to_string()
+        ),
+        evidence.recover_identity().unwrap_err()
+    );
+}
+
+#[test]
+fn signals_in_different_slots_do_not_reveal_identity() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let topic = Topic::from(TOPIC)
+        .with_message_limit(2)
+        .unwrap()
+        .with_slashing();
+
+    let first = access_set
+        .make_rate_limited_signal(&identities[1], topic, b"Yes", 0, &prover)
+        .unwrap();
+    let second = access_set
+        .make_rate_limited_signal(&identities[1], topic, b"No", 1, &prover)
+        .unwrap();
+    let err =
+        recover_identity_from_double_signal(topic, b"Yes", &first, b"No", &second).unwrap_err();
+    assert_eq!(
+        SemaphoreError::InvalidEvidence("signals have different nullifiers".to_string()),
+        err
+    );
+
+    // reusing a slot reveals the identity
+    let third = access_set
+        .make_rate_limited_signal(&identities[1], topic, b"Maybe", 1, &prover)
+        .unwrap();
+    let identity =
+        recover_identity_from_double_signal(topic, b"No", &second, b"Maybe", &third).unwrap();
+    assert_eq!(identities[1], identity);
+}
+
+#[test]
+fn forged_evidence_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let topic = Topic::from(TOPIC).with_slashing();
+
+    let first = access_set
+        .make_signal(&identities[2], topic, b"Yes", &prover)
+        .unwrap();
+    let second = access_set
+        .make_signal(&identities[2], topic, b"No", &prover)
+        .unwrap();
+    let mut evidence = SlashingEvidence::new(topic, b"Yes", &first, b"No", &second).unwrap();
+    evidence.key_shares[1].1[0] += Felt::ONE;
+    assert_eq!(
+        SemaphoreError::InvalidEvidence(
+            "recovered identity does not match the nullifier".to_string()
+        ),
+        evidence.recover_identity().unwrap_err()
+    );
+
+    // evidence cannot be built for a topic which does not use slashing
+    let err = SlashingEvidence::new(TOPIC, b"Yes", &first, b"No", &second).unwrap_err();
+    assert_eq!(
+        SemaphoreError::InvalidEvidence("topic does not use slashing".to_string()),
+        err
+    );
+}
@@ -0,0 +1,157 @@
+use super::{build_access_set, temp_path, MESSAGE, TOPIC};
+use crate::{
+    FileNullifierStore, Hasher, InMemoryNullifierStore, NullifierStore, Rescue, SemaphoreError,
+    SemaphoreProver,
+};
+use std::fs;
+
+// ACCEPTING SIGNALS
+// ================================================================================================
+
+#[test]
+fn duplicate_signal_is_rejected() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let mut store = InMemoryNullifierStore::new();
+
+    let signal = access_set
+        .make_signal(&identities[1], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(access_set
+        .accept_signal(TOPIC, MESSAGE, signal.clone(), &mut store)
+        .is_ok());
+    assert_eq!(1, store.len());
+
+    // the same signal cannot be accepted twice
+    let result = access_set.accept_signal(TOPIC, MESSAGE, signal.clone(), &mut store);
+    assert!(matches!(
+        result,
+        Err(SemaphoreError::DuplicateNullifier { nullifier, .. }) if nullifier == signal.nullifier
+    ));
+
+    // a new signal by the same user on the same topic has the same nullifier
+    let signal = access_set
+        .make_signal(&identities[1], TOPIC, b"No", &prover)
+        .unwrap();
+    let result = access_set.accept_signal(TOPIC, b"No", signal, &mut store);
+    assert!(matches!(
+        result,
+        Err(SemaphoreError::DuplicateNullifier { .. })
+    ));
+
+    // signals by other users, or on other topics, are accepted
+    let signal = access_set
+        .make_signal(&identities[2], TOPIC, MESSAGE, &prover)
+        .unwrap();
+    assert!(access_set
+        .accept_signal(TOPIC, MESSAGE, signal, &mut store)
+        .is_ok());
+    let signal = access_set
+        .make_signal(&identities[1], "other topic", MESSAGE, &prover)
+        .unwrap();
+    assert!(access_set
+        .accept_signal("other topic", MESSAGE, signal, &mut store)
+        .is_ok());
+    assert_eq!(3, store.len());
+}
+
+#[test]
+fn invalid_signal_is_not_recorded() {
+    let (identities, access_set) = build_access_set(8);
+    let mut store = InMemoryNullifierStore::new();
+
+    let signal = access_set
+        .make_signal(&identities[1], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+    let result = access_set.accept_signal(TOPIC, b"No", signal, &mut store);
+    assert!(matches!(result, Err(SemaphoreError::VerificationFailed(_))));
+    assert!(store.is_empty());
+}
+
+// FILE-BACKED STORE
+// ================================================================================================
+
+#[test]
+fn file_store_persists_nullifiers() {
+    let path = temp_path("file_store_persists_nullifiers");
+    let topic = Rescue::hash(TOPIC.as_bytes());
+    let nullifier1 = Rescue::hash(b"nullifier 1");
+    let nullifier2 = Rescue::hash(b"nullifier 2");
+
+    {
+        let mut store = FileNullifierStore::open(&path).unwrap();
+        assert!(store.is_empty());
+        assert!(store.insert(topic, nullifier1).unwrap());
+        assert!(!store.insert(topic, nullifier1).unwrap());
+        assert!(store.insert(topic, nullifier2).unwrap());
+    }
+    assert_eq!(128, fs::metadata(&path).unwrap().len());
+
+    // re-opening the store restores all records, and duplicates are still rejected
+    let mut store = FileNullifierStore::open(&path).unwrap();
+    assert_eq!(2, store.len());
+    assert!(store.contains(topic, nullifier1).unwrap());
+    assert!(store.contains(topic, nullifier2).unwrap());
+    assert!(!store.contains(nullifier1, topic).unwrap());
+    assert!(!store.insert(topic, nullifier2).unwrap());
+    drop(store);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn file_store_recovers_from_interrupted_write() {
+    let path = temp_path("file_store_recovers_from_interrupted_write");
+    let topic = Rescue::hash(TOPIC.as_bytes());
+    let nullifier1 = Rescue::hash(b"nullifier 1");
+    let nullifier2 = Rescue::hash(b"nullifier 2");
+
+    let mut store = FileNullifierStore::open(&path).unwrap();
+    assert!(store.insert(topic, nullifier1).unwrap());
+    drop(store);
+
+    // an interrupted write leaves a partial record at the end of the file
+    let mut bytes = fs::read(&path).unwrap();
+    bytes.extend_from_slice(&[1, 2, 3]);
+    fs::write(&path, bytes).unwrap();
+
+    // the partial record is dropped when the store is re-opened, and new records are appended
+    // after the last complete record
+    let mut store = FileNullifierStore::open(&path).unwrap();
+    assert_eq!(1, store.len());
+    assert_eq!(64, fs::metadata(&path).unwrap().len());
+    assert!(store.contains(topic, nullifier1).unwrap());
+    assert!(store.insert(topic, nullifier2).unwrap());
+    drop(store);
+
+    let store = FileNullifierStore::open(&path).unwrap();
+    assert_eq!(2, store.len());
+    assert!(store.contains(topic, nullifier2).unwrap());
+    assert_eq!(128, fs::metadata(&path).unwrap().len());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn file_store_rejects_duplicate_signals() {
+    let path = temp_path("file_store_rejects_duplicate_signals");
+    let (identities, access_set) = build_access_set(8);
+    let signal = access_set
+        .make_signal(&identities[6], TOPIC, MESSAGE, &SemaphoreProver::default())
+        .unwrap();
+
+    let mut store = FileNullifierStore::open(&path).unwrap();
+    assert!(access_set
+        .accept_signal(TOPIC, MESSAGE, signal.clone(), &mut store)
+        .is_ok());
+    drop(store);
+
+    let mut store = FileNullifierStore::open(&path).unwrap();
+    let result = access_set.accept_signal(TOPIC, MESSAGE, signal, &mut store);
+    assert!(matches!(
+        result,
+        Err(SemaphoreError::DuplicateNullifier { .. })
+    ));
+
+    fs::remove_file(&path).unwrap();
+}
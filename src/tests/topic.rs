@@ -0,0 +1,129 @@
+use super::{build_access_set, MESSAGE, TOPIC};
+use crate::{
+    hash_bytes, ExternalNullifier, Hasher, Rescue, SemaphoreError, SemaphoreProver, Topic,
+};
+
+// TOPICS
+// ================================================================================================
+
+#[test]
+fn string_topic_is_hash_of_string() {
+    assert_eq!(Rescue::hash(TOPIC.as_bytes()), Topic::from(TOPIC).digest());
+}
+
+#[test]
+fn hash_bytes_matches_rescue_hash() {
+    // Rp64_256::hash() can be used only for inputs of up to 56 bytes
+    let bytes = (0..=56).map(|i| (i * 37) as u8).collect::<Vec<_>>();
+    for len in 0..=56 {
+        assert_eq!(Rescue::hash(&bytes[..len]), hash_bytes(&bytes[..len]));
+    }
+}
+
+#[test]
+fn long_topics_and_messages_can_be_hashed() {
+    let long_topic = "a".repeat(100);
+    assert_ne!(
+        Topic::from(long_topic.as_str()),
+        Topic::from(&long_topic[..99])
+    );
+    assert_ne!(hash_bytes(&[0; 57]), hash_bytes(&[0; 58]));
+}
+
+#[test]
+fn external_nullifier_encoding_is_canonical() {
+    let external_nullifier = ExternalNullifier::new(7, "poll", 3);
+
+    let mut expected = b"\xffsemaphore/external-nullifier/v1".to_vec();
+    expected.extend_from_slice(&[7, 0, 0, 0, 0, 0, 0, 0]);
+    expected.extend_from_slice(&[3, 0, 0, 0, 0, 0, 0, 0]);
+    expected.extend_from_slice(b"poll");
+    assert_eq!(expected, external_nullifier.to_bytes());
+    assert_eq!(Rescue::hash(&expected), external_nullifier.topic().digest());
+}
+
+#[test]
+fn distinct_external_nullifiers_have_distinct_topics() {
+    let base = ExternalNullifier::new(1, "poll", 0);
+    let others = [
+        ExternalNullifier::new(2, "poll", 0),
+        ExternalNullifier::new(1, "poll2", 0),
+        ExternalNullifier::new(1, "", 0),
+        base.next_epoch().unwrap(),
+        base.for_epoch(u64::MAX),
+    ];
+    for other in others.iter() {
+        assert_ne!(base.topic(), other.topic(), "{:?}", other);
+    }
+    assert_ne!(Topic::from("poll"), base.topic());
+}
+
+#[test]
+fn string_topics_do_not_collide_with_external_nullifiers() {
+    // the encoding of an external nullifier is not a valid string, and thus, no string topic is
+    // hashed from the same bytes
+    let external_nullifier = ExternalNullifier::new(7, "poll", 3);
+    let encoding = external_nullifier.to_bytes();
+    assert!(std::str::from_utf8(&encoding).is_err());
+
+    // a string which spells out the rest of the encoding yields a different topic
+    let spoofed = std::str::from_utf8(&encoding[1..]).unwrap();
+    assert_ne!(external_nullifier.topic(), Topic::from(spoofed));
+}
+
+#[test]
+fn epochs_are_derived_from_timestamps() {
+    let day = 24 * 60 * 60;
+    let external_nullifier =
+        ExternalNullifier::at_timestamp(1, "daily poll", 10 * day + 5, day).unwrap();
+    assert_eq!(10, external_nullifier.epoch);
+    assert_eq!(
+        external_nullifier,
+        ExternalNullifier::new(1, "daily poll", 10)
+    );
+    assert_eq!(11, external_nullifier.next_epoch().unwrap().epoch);
+
+    let last_second = ExternalNullifier::at_timestamp(1, "daily poll", 11 * day - 1, day).unwrap();
+    assert_eq!(external_nullifier, last_second);
+}
+
+#[test]
+fn invalid_epochs_are_rejected() {
+    assert_eq!(
+        SemaphoreError::InvalidEpochLength,
+        ExternalNullifier::at_timestamp(1, "daily poll", 5, 0).unwrap_err()
+    );
+    assert_eq!(
+        SemaphoreError::EpochOverflow,
+        ExternalNullifier::new(1, "poll", u64::MAX)
+            .next_epoch()
+            .unwrap_err()
+    );
+}
+
+// SIGNALS ON EXTERNAL NULLIFIERS
+// ================================================================================================
+
+#[test]
+fn signals_in_different_epochs_have_different_nullifiers() {
+    let (identities, access_set) = build_access_set(8);
+    let prover = SemaphoreProver::default();
+    let epoch1 = ExternalNullifier::new(1, "weekly poll", 1);
+    let epoch2 = epoch1.next_epoch().unwrap();
+
+    let signal1 = access_set
+        .make_signal(&identities[4], &epoch1, MESSAGE, &prover)
+        .unwrap();
+    let signal2 = access_set
+        .make_signal(&identities[4], &epoch2, MESSAGE, &prover)
+        .unwrap();
+    assert_ne!(signal1.nullifier, signal2.nullifier);
+
+    assert!(access_set
+        .verify_signal(&epoch1, MESSAGE, signal1.clone())
+        .is_ok());
+    assert!(access_set.verify_signal(&epoch2, MESSAGE, signal2).is_ok());
+
+    // a signal made in one epoch is not valid in another epoch
+    assert!(access_set.verify_signal(&epoch2, MESSAGE, signal1).is_err());
+}
@@ -0,0 +1,188 @@
+use super::{air::RelaxedAir, build_access_set, MESSAGE, TOPIC};
+use crate::{
+    Digest, Felt, FieldElement, Hasher, Prover, PublicInputs, Rescue, SemaphoreAir,
+    SemaphoreProver, Topic, Trace, TraceTable, HASH_CYCLE_LEN, MAX_TREE_DEPTH,
+};
+use rand_utils::rand_array;
+use winterfell::{Air, EvaluationFrame, TraceInfo};
+
+// UNDERCONSTRAINED CELL DETECTOR
+// ================================================================================================
+
+/// A cell of an honest execution trace which can be changed without violating any of the AIR
+/// constraints which determine its value.
+#[derive(Debug, PartialEq)]
+struct FreeCell {
+    column: usize,
+    step: usize,
+}
+
+/// Returns all cells of the provided honest trace which are not determined by the specified AIR.
+///
+/// Every cell of the trace is perturbed one at a time, and the perturbed trace is checked
+/// directly against the AIR built from the public inputs of the honest trace: the cell is
+/// reported if the perturbation violates neither an assertion nor a transition constraint over
+/// the frame which ends at the perturbed step. Such a cell is not fixed by the public inputs nor
+/// derived from the previous row, and thus, a prover can put any value into it and compute the
+/// rest of the trace from there. At the first step, these must be exactly the secret inputs of
+/// the computation; any other cell there is a hash input which the verifier does not pin.
+///
+/// Only the frame which ends at the perturbed step is checked on purpose: a cell which feeds the
+/// next row (e.g., an input of a Rescue round) also breaks the frame which starts at it, but a
+/// prover who chooses its value can simply compute the next rows from it.
+fn find_free_cells<A>(trace: &TraceTable<Felt>) -> Vec<FreeCell>
+where
+    A: Air<BaseField = Felt, PublicInputs = PublicInputs>,
+{
+    let prover = SemaphoreProver::default();
+    let air = A::new(
+        TraceInfo::new(trace.width(), trace.length()),
+        prover.get_pub_inputs(trace),
+        prover.options().clone(),
+    );
+    let assertions = air.get_assertions();
+    let periodic_columns = air.get_periodic_column_values();
+    let mut periodic_values = vec![Felt::ZERO; periodic_columns.len()];
+    let mut frame = EvaluationFrame::new(trace.width());
+    let mut evaluations = vec![Felt::ZERO; air.num_transition_constraints()];
+
+    let mut result = Vec::new();
+    for step in 0..trace.length() {
+        for column in 0..trace.width() {
+            let value = trace.get(column, step) + Felt::ONE;
+
+            // check all assertions placed against the perturbed cell
+            let mut is_free = true;
+            for assertion in assertions.iter().filter(|a| a.register() == column) {
+                assertion.apply(trace.length(), |s, expected| {
+                    if s == step && value != expected {
+                        is_free = false;
+                    }
+                });
+            }
+
+            // evaluate transition constraints for the previous row and the perturbed row
+            if is_free && step > 0 {
+                for (v, periodic_column) in periodic_values.iter_mut().zip(periodic_columns.iter())
+                {
+                    *v = periodic_column[(step - 1) % periodic_column.len()];
+                }
+                trace.read_row_into(step - 1, frame.current_mut());
+                trace.read_row_into(step, frame.next_mut());
+                frame.next_mut()[column] = value;
+
+                evaluations.iter_mut().for_each(|v| *v = Felt::ZERO);
+                air.evaluate_transition(&frame, &periodic_values, &mut evaluations);
+                is_free = evaluations.iter().all(|&v| v == Felt::ZERO);
+            }
+
+            if is_free {
+                result.push(FreeCell { column, step });
+            }
+        }
+    }
+
+    result
+}
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn all_cells_are_constrained_or_expected_free() {
+    let (identities, _) = build_access_set(1);
+    let topic = Topic::from(TOPIC).with_message_limit(5).unwrap();
+    let message = Rescue::hash(MESSAGE);
+
+    // the trace depends only on the length of the Merkle path and not on whether the path leads
+    // to a real root, and thus, a path of random nodes can be used for every supported depth
+    for depth in 1..=MAX_TREE_DEPTH {
+        let key_index = 0x5555_5555 & ((1 << depth) - 1);
+        let merkle_path = (0..=depth)
+            .map(|_| Digest::new(rand_array()))
+            .collect::<Vec<_>>();
+        let trace = SemaphoreProver::default()
+            .build_trace(&identities[0], key_index, topic, 3, message, &merkle_path)
+            .unwrap();
+
+        for cell in find_free_cells::<SemaphoreAir>(&trace) {
+            let index_bit = trace.get(24, cell.step);
+            assert!(
+                is_expected_free_cell(cell.column, cell.step, index_bit),
+                "cell ({}, {}) is not constrained at depth {}",
+                cell.column,
+                cell.step,
+                depth
+            );
+        }
+    }
+}
+
+#[test]
+fn missing_capacity_assertion_is_detected() {
+    let (identities, access_set) = build_access_set(8);
+    let topic = Topic::from(TOPIC);
+    let merkle_path = access_set.get_key_path(3).unwrap();
+    let trace = SemaphoreProver::default()
+        .build_trace(
+            &identities[3],
+            3,
+            topic,
+            0,
+            Rescue::hash(MESSAGE),
+            &merkle_path,
+        )
+        .unwrap();
+
+    // the capacity of the nullifier hash is pinned by Semaphore AIR, but once the assertions
+    // which pin it are removed, a prover can put any value into it
+    let capacity = FreeCell {
+        column: 12,
+        step: 0,
+    };
+    assert!(!find_free_cells::<SemaphoreAir>(&trace).contains(&capacity));
+    assert!(find_free_cells::<RelaxedAir>(&trace).contains(&capacity));
+}
+
+/// Returns true if the value at the specified cell is not expected to be determined by Semaphore
+/// AIR.
+///
+/// At the first step, these are only the secret inputs of the computation and their copies; the
+/// copies are tied to the inputs by constraints over the first row, which the detector does not
+/// see, as no frame ends at the first row. Every other cell of the first row must be pinned by an
+/// assertion. At the start of every following hash cycle, these are the Merkle path node which is
+/// placed next to the accumulated hash according to the index bit, and the nullifier section of the
+/// trace, which is not used after the nullifier is computed; the exceptions are the second cycle,
+/// where the path node is the zero padding of the identity commitment and columns [20..24] hold the
+/// message hash, and columns [20..24], from which the x coordinate of the key share is computed.
+fn is_expected_free_cell(column: usize, step: usize, index_bit: Felt) -> bool {
+    if step == 0 {
+        return match column {
+            // the nullifier secret and the trapdoor of the identity
+            4..=11 => true,
+            // the message index, which is the second capacity element of the nullifier hash
+            13 => true,
+            // the copy of the nullifier secret in the nullifier hash state
+            16..=19 => true,
+            // the key index accumulator; its value at the root step is pinned by an assertion
+            25 => true,
+            // the range checks of the message index; these are set from column 13
+            26 | 27 => true,
+            // the key share accumulators; these start as copies of the identity secrets
+            28..=35 => true,
+            _ => false,
+        };
+    }
+    if step % HASH_CYCLE_LEN != 0 {
+        return false;
+    }
+
+    let is_nullifier_section = matches!(column, 12..=19);
+    let is_path_node = step > HASH_CYCLE_LEN
+        && if index_bit == Felt::ZERO {
+            (8..12).contains(&column)
+        } else {
+            (4..8).contains(&column)
+        };
+    is_nullifier_section || is_path_node
+}
@@ -0,0 +1,208 @@
+use super::{Digest, Felt, Rescue, SemaphoreError, MAX_MESSAGE_LIMIT};
+use winterfell::crypto::ElementHasher;
+
+// TOPIC
+// ================================================================================================
+
+/// Defines a topic on which signals are made.
+///
+/// A topic is represented by a hash of its description. A topic can be created from a free-form
+/// string, in which case the hash is computed simply as hash(string), or from an
+/// [ExternalNullifier], in which case the hash is computed from the canonical encoding of the
+/// external nullifier. The encoding of an external nullifier is never a valid UTF-8 string, and
+/// thus, a string topic cannot have the same hash as a topic of an external nullifier.
+///
+/// A topic also defines the maximum number of signals every user can make on it. By default, this
+/// limit is 1, and thus, the same identity always produces the same nullifier on the topic. For a
+/// topic with a limit of k, every signal carries a (private) message index in the range [0, k),
+/// and a nullifier is computed from the (nullifier secret, topic, message index) triple. Thus,
+/// every user can signal up to k times on the topic before the nullifiers start repeating.
+///
+/// A topic may also use slashing. In this case, every signal reveals a share of the identity of
+/// the signaller, and two signals with the same nullifier but different messages reveal the
+/// identity itself (see [SlashingEvidence](crate::SlashingEvidence)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Topic {
+    digest: Digest,
+    message_limit: u32,
+    slashing: bool,
+}
+
+impl Topic {
+    /// Returns a topic with the specified hash which allows a single signal per user.
+    pub fn new(digest: Digest) -> Self {
+        Self {
+            digest,
+            message_limit: 1,
+            slashing: false,
+        }
+    }
+
+    /// Returns this topic with the maximum number of signals per user set to the specified limit.
+    ///
+    /// # Errors
+    /// Returns an error if the limit is zero or greater than [MAX_MESSAGE_LIMIT].
+    pub fn with_message_limit(mut self, message_limit: u32) -> Result<Self, SemaphoreError> {
+        if message_limit == 0 || message_limit > MAX_MESSAGE_LIMIT {
+            return Err(SemaphoreError::InvalidMessageLimit(message_limit));
+        }
+        self.message_limit = message_limit;
+        Ok(self)
+    }
+
+    /// Returns this topic with slashing enabled.
+    pub fn with_slashing(mut self) -> Self {
+        self.slashing = true;
+        self
+    }
+
+    /// Returns the hash of this topic.
+    pub fn digest(&self) -> Digest {
+        self.digest
+    }
+
+    /// Returns the maximum number of signals every user can make on this topic.
+    pub fn message_limit(&self) -> u32 {
+        self.message_limit
+    }
+
+    /// Returns true if signals on this topic must reveal key shares.
+    pub fn slashing(&self) -> bool {
+        self.slashing
+    }
+}
+
+impl From<&str> for Topic {
+    fn from(topic: &str) -> Self {
+        Self::new(hash_bytes(topic.as_bytes()))
+    }
+}
+
+impl From<&ExternalNullifier> for Topic {
+    fn from(external_nullifier: &ExternalNullifier) -> Self {
+        Self::new(hash_bytes(&external_nullifier.to_bytes()))
+    }
+}
+
+impl From<ExternalNullifier> for Topic {
+    fn from(external_nullifier: ExternalNullifier) -> Self {
+        Self::from(&external_nullifier)
+    }
+}
+
+// EXTERNAL NULLIFIER
+// ================================================================================================
+
+/// Defines a structured description of a topic.
+///
+/// An external nullifier consists of:
+/// - An application ID, which separates topics of different applications using the same access
+///   set.
+/// - A scope, which identifies a topic within an application (e.g., a name of a poll).
+/// - An epoch, which allows the same scope to be reused periodically (e.g., for a recurring poll).
+///   Every user can signal once per epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalNullifier {
+    pub app_id: u64,
+    pub scope: String,
+    pub epoch: u64,
+}
+
+impl ExternalNullifier {
+    /// Domain separation tag which is prepended to the encoding of every external nullifier.
+    ///
+    /// The tag starts with byte 0xFF, which never occurs in UTF-8 strings; thus, the encoding of
+    /// an external nullifier cannot be the same as the bytes of a string topic.
+    const DOMAIN_TAG: &'static [u8] = b"\xffsemaphore/external-nullifier/v1";
+
+    /// Returns a new external nullifier for the specified application, scope, and epoch.
+    pub fn new(app_id: u64, scope: &str, epoch: u64) -> Self {
+        Self {
+            app_id,
+            scope: scope.to_string(),
+            epoch,
+        }
+    }
+
+    /// Returns an external nullifier with the same application ID and scope as this one, but for
+    /// the specified epoch.
+    pub fn for_epoch(&self, epoch: u64) -> Self {
+        Self {
+            epoch,
+            ..self.clone()
+        }
+    }
+
+    /// Returns an external nullifier for the epoch which follows the epoch of this one.
+    ///
+    /// # Errors
+    /// Returns an error if the epoch of this external nullifier is u64::MAX.
+    pub fn next_epoch(&self) -> Result<Self, SemaphoreError> {
+        let epoch = self
+            .epoch
+            .checked_add(1)
+            .ok_or(SemaphoreError::EpochOverflow)?;
+        Ok(self.for_epoch(epoch))
+    }
+
+    /// Returns an external nullifier for the epoch containing the specified timestamp.
+    ///
+    /// Epochs are assumed to be consecutive intervals of `epoch_len` seconds starting at the
+    /// timestamp 0; thus, for example, with `epoch_len` of one day, every day is a new epoch.
+    ///
+    /// # Errors
+    /// Returns an error if `epoch_len` is zero.
+    pub fn at_timestamp(
+        app_id: u64,
+        scope: &str,
+        timestamp: u64,
+        epoch_len: u64,
+    ) -> Result<Self, SemaphoreError> {
+        if epoch_len == 0 {
+            return Err(SemaphoreError::InvalidEpochLength);
+        }
+        Ok(Self::new(app_id, scope, timestamp / epoch_len))
+    }
+
+    /// Returns the topic described by this external nullifier.
+    pub fn topic(&self) -> Topic {
+        self.into()
+    }
+
+    /// Returns the canonical encoding of this external nullifier.
+    ///
+    /// The encoding consists of the domain separation tag, followed by the application ID and the
+    /// epoch (both as 8 little-endian bytes), followed by the bytes of the scope. Since all fields
+    /// but the last one have fixed size, distinct external nullifiers have distinct encodings; and
+    /// since the tag is not valid UTF-8, no encoding is the same as the bytes of a string.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Self::DOMAIN_TAG.to_vec();
+        result.extend_from_slice(&self.app_id.to_le_bytes());
+        result.extend_from_slice(&self.epoch.to_le_bytes());
+        result.extend_from_slice(self.scope.as_bytes());
+        result
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a hash of the provided bytes.
+///
+/// The bytes are split into 7-byte chunks, with the last chunk padded by a single byte with value
+/// 1, and every chunk is converted into a field element. For inputs of up to 56 bytes, the result
+/// is the same as the result of Rp64_256::hash(); however, Rp64_256::hash() panics for longer
+/// inputs, and thus, this function should be used to hash arbitrary byte strings instead.
+pub fn hash_bytes(bytes: &[u8]) -> Digest {
+    let num_elements = bytes.len().div_ceil(7);
+    let mut elements = Vec::with_capacity(num_elements);
+    for (i, chunk) in bytes.chunks(7).enumerate() {
+        let mut buf = [0u8; 8];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        if i == num_elements - 1 {
+            buf[chunk.len()] = 1;
+        }
+        elements.push(Felt::new(u64::from_le_bytes(buf)));
+    }
+    Rescue::hash_elements(&elements)
+}
@@ -0,0 +1,219 @@
+use super::{
+    get_trace_length, hash_bytes, incremental::RootHistory, Digest, NullifierStore, PublicInputs,
+    SemaphoreAir, SemaphoreError, Signal, Topic, VerificationPolicy, MAX_TREE_DEPTH,
+};
+
+// SIGNAL VERIFIER
+// ================================================================================================
+
+/// Verifies signals against a root of an access set Merkle tree.
+///
+/// Unlike an access set, a verifier does not need to know the public keys in the set; it needs
+/// only the root of the tree, the depth of the tree, and the policy which proofs must satisfy.
+/// Thus, signals can be verified by parties which do not hold the access set (e.g., auditors or
+/// light clients).
+///
+/// A verifier may also accept signals made against any of several roots of the same tree (e.g.,
+/// recent roots of a tree which grows over time). Every signal states the root it was made
+/// against, and thus, its proof is verified only once regardless of the number of roots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignalVerifier {
+    roots: Vec<Digest>,
+    depth: usize,
+    policy: VerificationPolicy,
+}
+
+impl SignalVerifier {
+    /// Returns a new verifier for an access set with the specified root and depth, which accepts
+    /// only signals satisfying the provided policy.
+    ///
+    /// # Errors
+    /// Returns an error if the depth is not between 1 and 32.
+    pub fn new(
+        root: Digest,
+        depth: usize,
+        policy: VerificationPolicy,
+    ) -> Result<Self, SemaphoreError> {
+        Self::with_roots(vec![root], depth, policy)
+    }
+
+    /// Returns a new verifier for an access set with the specified depth which accepts signals
+    /// made against any of the provided roots; roots must be ordered from the oldest to the most
+    /// recent one.
+    ///
+    /// # Errors
+    /// Returns an error if the list of roots is empty, or if the depth is not between 1 and 32.
+    pub fn with_roots(
+        roots: Vec<Digest>,
+        depth: usize,
+        policy: VerificationPolicy,
+    ) -> Result<Self, SemaphoreError> {
+        if !(1..=MAX_TREE_DEPTH).contains(&depth) {
+            return Err(SemaphoreError::InvalidDepth(depth));
+        }
+        if roots.is_empty() {
+            return Err(SemaphoreError::InvalidHistorySize(0));
+        }
+        Ok(Self {
+            roots,
+            depth,
+            policy,
+        })
+    }
+
+    /// Returns a new verifier for an access set with the specified depth which accepts signals
+    /// made against any of the roots in the provided history.
+    pub(crate) fn from_root_history(
+        roots: &RootHistory,
+        depth: usize,
+        policy: VerificationPolicy,
+    ) -> Self {
+        Self {
+            roots: roots.to_vec(),
+            depth,
+            policy,
+        }
+    }
+
+    /// Returns the most recent root of the access set this verifier verifies signals against.
+    pub fn root(&self) -> Digest {
+        // with_roots() rejects empty lists of roots, and a root history always holds at least
+        // one root; thus, a verifier always has a root
+        *self.roots.last().expect("verifier has no roots")
+    }
+
+    /// Returns the depth of the access set this verifier verifies signals against.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the policy which signals must satisfy to be accepted by this verifier.
+    pub fn policy(&self) -> &VerificationPolicy {
+        &self.policy
+    }
+
+    /// Returns the length of the execution trace of signals made for the access set.
+    pub fn trace_length(&self) -> usize {
+        get_trace_length(self.depth)
+    }
+
+    /// Returns Ok(()) if the provided signal is a valid signal with the specified message on the
+    /// specified topic by someone with a key from the access set.
+    ///
+    /// A signal made for an access set of a different depth or against an unknown root, or a proof
+    /// which does not satisfy the verification policy of this verifier, is rejected before the
+    /// proof itself is verified.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The signal was made for an access set of a different depth, or the length of the
+    ///   execution trace of the proof does not match the depth of the access set.
+    /// - The signal was made against a root which is not accepted by this verifier.
+    /// - The proof does not satisfy the verification policy.
+    /// - The signal does not contain a key share while the topic uses slashing, or vice versa.
+    /// - The proof is not valid against the root of the signal.
+    pub fn verify_signal(
+        &self,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        signal: Signal,
+    ) -> Result<(), SemaphoreError> {
+        let topic = topic.into();
+
+        // make sure the signal was made for an access set of the same depth; the depth must be
+        // checked explicitly because traces for several depths are padded to the same length
+        if signal.depth != self.depth {
+            return Err(SemaphoreError::SignalDepthMismatch {
+                expected: self.depth,
+                actual: signal.depth,
+            });
+        }
+
+        // make sure the signal was made against one of the roots accepted by this verifier; the
+        // proof is then verified only against that root
+        if !self.roots.contains(&signal.root) {
+            return Err(SemaphoreError::UnknownRoot(signal.root));
+        }
+
+        // make sure the proof was generated for a Merkle tree of the same depth as the access set
+        let trace_length = signal.proof.trace_length();
+        if trace_length != self.trace_length() {
+            return Err(SemaphoreError::InvalidTraceLength {
+                expected: self.trace_length(),
+                actual: trace_length,
+            });
+        }
+
+        // make sure the proof satisfies the verification policy
+        self.policy.check(&signal.proof)?;
+
+        // make sure the signal reveals a key share if and only if the topic uses slashing
+        if topic.slashing() != signal.key_share.is_some() {
+            return Err(if topic.slashing() {
+                SemaphoreError::MissingKeyShare
+            } else {
+                SemaphoreError::UnexpectedKeyShare
+            });
+        }
+
+        // verify the STARK proof against the root the signal was made against
+        let pub_inputs = PublicInputs {
+            tree_root: signal.root,
+            tree_depth: self.depth,
+            nullifier: signal.nullifier,
+            topic: topic.digest(),
+            message: hash_bytes(message),
+            message_limit: topic.message_limit(),
+            key_share: signal.key_share,
+        };
+        winterfell::verify::<SemaphoreAir>(signal.proof, pub_inputs)
+            .map_err(SemaphoreError::VerificationFailed)
+    }
+
+    /// Verifies the provided signal and records its nullifier in the provided store.
+    ///
+    /// A signal is accepted only if it is valid (as defined by [SignalVerifier::verify_signal()]),
+    /// and no signal with the same nullifier has been accepted on the same topic before.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The signal is not valid.
+    /// - A signal with the same nullifier has already been recorded in the store for this topic
+    ///   ([SemaphoreError::DuplicateNullifier]).
+    /// - The store could not be read or updated ([SemaphoreError::Store]).
+    pub fn accept_signal<S: NullifierStore>(
+        &self,
+        topic: impl Into<Topic>,
+        message: &[u8],
+        signal: Signal,
+        store: &mut S,
+    ) -> Result<(), SemaphoreError> {
+        let topic = topic.into();
+        let nullifier = signal.nullifier;
+        let duplicate = SemaphoreError::DuplicateNullifier {
+            topic: topic.digest(),
+            nullifier,
+        };
+
+        // check for a duplicate before verifying the proof as the check is much cheaper
+        if store
+            .contains(topic.digest(), nullifier)
+            .map_err(SemaphoreError::Store)?
+        {
+            return Err(duplicate);
+        }
+
+        self.verify_signal(topic, message, signal)?;
+
+        // the store checks for the duplicate again when recording the nullifier; this way, the
+        // same nullifier cannot be recorded twice even if signals are accepted concurrently
+        if !store
+            .insert(topic.digest(), nullifier)
+            .map_err(SemaphoreError::Store)?
+        {
+            return Err(duplicate);
+        }
+
+        Ok(())
+    }
+}